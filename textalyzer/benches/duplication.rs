@@ -0,0 +1,97 @@
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use textalyzer::duplication::find_multi_line_duplications;
+use textalyzer::similarity::find_similar_blocks;
+use textalyzer::types::{FileEntry, MappedContent};
+
+const NUM_FILES: usize = 20;
+const LINES_PER_FILE: usize = 2000;
+const DUPLICATED_BLOCKS: usize = 30;
+const BLOCK_SIZE: usize = 5;
+const REPEATED_LINES: usize = 100_000;
+const SIMILAR_LINES: usize = 3000;
+
+/// Files with unique lines, duplicated blocks at regular intervals,
+/// and many repeated lines like `}`
+fn generate_files() -> Vec<FileEntry> {
+  (0..NUM_FILES)
+    .map(|i| {
+      let mut content = String::new();
+      for j in 0..LINES_PER_FILE {
+        if j % 50 == 0 && j < DUPLICATED_BLOCKS * 50 {
+          let block_id = j / 50;
+          for k in 0..BLOCK_SIZE {
+            content.push_str(&format!(
+              "This is duplicated block {block_id} line {k}\n"
+            ));
+          }
+        } else if j % 3 == 0 {
+          content.push_str("}\n");
+        } else {
+          content.push_str(&format!("Unique line {j} in file {i}\n"));
+        }
+      }
+      FileEntry {
+        name: format!("file{i}.txt"),
+        content: MappedContent::String(content),
+      }
+    })
+    .collect()
+}
+
+/// A single file which consists only of the same line
+fn generate_repetitive_file() -> Vec<FileEntry> {
+  vec![FileEntry {
+    name: "repetitive.txt".to_string(),
+    content: MappedContent::String("x += 1;\n".repeat(REPEATED_LINES)),
+  }]
+}
+
+/// Two long files which differ only in a single line
+fn generate_similar_files() -> Vec<FileEntry> {
+  let file = |name: &str, changed_line: &str| {
+    let content = (0..SIMILAR_LINES)
+      .map(|idx| {
+        if idx == SIMILAR_LINES / 2 {
+          format!("{changed_line}\n")
+        } else {
+          format!("let value_{idx} = compute({idx}, {});\n", idx % 7)
+        }
+      })
+      .collect();
+    FileEntry {
+      name: name.to_string(),
+      content: MappedContent::String(content),
+    }
+  };
+  vec![
+    file("a.rs", "print(value);"),
+    file("b.rs", "log(other_value);"),
+  ]
+}
+
+fn multi_line_duplications(c: &mut Criterion) {
+  c.bench_function("find_multi_line_duplications", |b| {
+    b.iter_batched(
+      generate_files,
+      find_multi_line_duplications,
+      BatchSize::LargeInput,
+    )
+  });
+  c.bench_function("find_multi_line_duplications_repetitive", |b| {
+    b.iter_batched(
+      generate_repetitive_file,
+      find_multi_line_duplications,
+      BatchSize::LargeInput,
+    )
+  });
+}
+
+fn similar_blocks(c: &mut Criterion) {
+  let files = generate_similar_files();
+  c.bench_function("find_similar_blocks", |b| {
+    b.iter(|| find_similar_blocks(&files, 3, 0.9))
+  });
+}
+
+criterion_group!(benches, multi_line_duplications, similar_blocks);
+criterion_main!(benches);
@@ -0,0 +1,201 @@
+use crate::types::{BaselineItem, DuplicationItem};
+use std::collections::HashMap;
+use std::error::Error;
+use std::fs;
+
+const FNV_OFFSET_BASIS: u64 = 0xcbf29ce484222325;
+const FNV_PRIME: u64 = 0x100000001b3;
+
+/// Hash of a duplicated block which identifies it in a baseline.
+///
+/// Indentation, trailing whitespace, and empty lines are ignored,
+/// so the hash stays the same when the block is moved or reindented.
+/// It uses the 64 bit FNV-1a hash,
+/// which is stable across platforms and versions.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::baseline::content_hash;
+///
+/// assert_eq!(
+///   content_hash("fn main() {\n  run();\n}"),
+///   content_hash("    fn main() {\n\n        run();\n    }\n"),
+/// );
+/// assert_ne!(content_hash("a\nb"), content_hash("b\na"));
+/// ```
+pub fn content_hash(content: &str) -> String {
+  let mut hash = FNV_OFFSET_BASIS;
+  let lines = content
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty());
+  for (idx, line) in lines.enumerate() {
+    let separator: &[u8] = if idx == 0 { b"" } else { b"\n" };
+    for byte in separator.iter().chain(line.as_bytes()) {
+      hash ^= u64::from(*byte);
+      hash = hash.wrapping_mul(FNV_PRIME);
+    }
+  }
+  format!("{hash:016x}")
+}
+
+/// Read the content hashes of all duplications in a baseline file
+/// with their number of copies.
+/// The output of `duplication --json` can also be used as a baseline.
+pub fn load_baseline(
+  path: &str,
+) -> Result<HashMap<String, usize>, Box<dyn Error>> {
+  let json = fs::read_to_string(path)
+    .map_err(|error| format!("Could not read baseline {path}: {error}"))?;
+  let items: Vec<BaselineItem> = serde_json::from_str(&json)
+    .map_err(|error| format!("Invalid baseline {path}: {error}"))?;
+
+  let mut known = HashMap::new();
+  for item in items {
+    let hash = if item.hash.is_empty() {
+      content_hash(&item.duplication.content)
+    } else {
+      item.hash
+    };
+    let copies = if item.copies == 0 {
+      item.duplication.locations.len()
+    } else {
+      item.copies
+    };
+    let recorded = known.entry(hash).or_insert(0);
+    *recorded = copies.max(*recorded);
+  }
+  Ok(known)
+}
+
+/// Write all duplications with their content hash to a baseline file
+pub fn save_baseline(
+  path: &str,
+  duplications: &[DuplicationItem],
+) -> Result<(), Box<dyn Error>> {
+  let items: Vec<BaselineItem> = duplications
+    .iter()
+    .map(|duplication| BaselineItem {
+      hash: content_hash(&duplication.content),
+      copies: duplication.locations.len(),
+      duplication: duplication.clone(),
+    })
+    .collect();
+  fs::write(path, serde_json::to_string_pretty(&items)? + "\n")?;
+  Ok(())
+}
+
+/// Remove all duplications recorded in the baseline
+/// and return how many were removed.
+///
+/// `copies` returns the content of a duplication and its number of copies.
+/// Duplications with more copies than recorded are kept,
+/// as the block was copied again.
+pub fn remove_known<T>(
+  duplications: &mut Vec<T>,
+  known: &HashMap<String, usize>,
+  copies: impl Fn(&T) -> (&str, usize),
+) -> usize {
+  let count = duplications.len();
+  duplications.retain(|duplication| {
+    let (content, copies) = copies(duplication);
+    known
+      .get(&content_hash(content))
+      .is_none_or(|recorded| copies > *recorded)
+  });
+  count - duplications.len()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::DuplicationLocation;
+  use tempfile::tempdir;
+
+  fn item(content: &str, path: &str, line: u32) -> DuplicationItem {
+    DuplicationItem {
+      content: content.to_string(),
+      locations: vec![DuplicationLocation {
+        path: path.to_string(),
+        line,
+        end_line: line + 1,
+      }],
+    }
+  }
+
+  #[test]
+  fn test_content_hash() {
+    // FNV-1a test vector
+    assert_eq!(content_hash("a"), "af63dc4c8601ec8c");
+    assert_eq!(content_hash(""), "cbf29ce484222325");
+  }
+
+  #[test]
+  fn test_baseline_round_trip() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("baseline.json");
+    let path = path.to_str().unwrap();
+
+    save_baseline(path, &[item("old();\ncopy();", "a.rs", 3)]).unwrap();
+    let known = load_baseline(path).unwrap();
+
+    // Moved blocks are still known, changed ones are new
+    let mut duplications = vec![
+      item("  old();\n  copy();", "a.rs", 10),
+      item("new();\ncopy();", "b.rs", 1),
+    ];
+    let removed = remove_known(&mut duplications, &known, |item| {
+      (&item.content, item.locations.len())
+    });
+
+    assert_eq!(removed, 1);
+    assert_eq!(duplications, vec![item("new();\ncopy();", "b.rs", 1)]);
+  }
+
+  #[test]
+  fn test_remove_known_keeps_additional_copies() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("baseline.json");
+    let path = path.to_str().unwrap();
+
+    let mut known_item = item("old();\ncopy();", "a.rs", 3);
+    known_item.locations.push(DuplicationLocation {
+      path: "b.rs".to_string(),
+      line: 7,
+      end_line: 8,
+    });
+    save_baseline(path, std::slice::from_ref(&known_item)).unwrap();
+    let known = load_baseline(path).unwrap();
+    assert_eq!(known.get(&content_hash("old();\ncopy();")), Some(&2));
+
+    // A third copy of a known block is reported again
+    let mut copied_again = known_item.clone();
+    copied_again.locations.push(DuplicationLocation {
+      path: "c.rs".to_string(),
+      line: 1,
+      end_line: 2,
+    });
+    let mut duplications = vec![known_item, copied_again.clone()];
+    let removed = remove_known(&mut duplications, &known, |item| {
+      (&item.content, item.locations.len())
+    });
+
+    assert_eq!(removed, 1);
+    assert_eq!(duplications, vec![copied_again]);
+  }
+
+  #[test]
+  fn test_read_baseline_without_hashes() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("duplications.json");
+    let json = r#"[{
+      "content": "old();\ncopy();",
+      "locations": [{ "path": "a.rs", "line": 3 }]
+    }]"#;
+    fs::write(&path, json).unwrap();
+
+    let known = load_baseline(path.to_str().unwrap()).unwrap();
+    assert_eq!(known.get(&content_hash("old();\ncopy();")), Some(&1));
+  }
+}
@@ -0,0 +1,216 @@
+use crate::types::{CharFrequencyItem, CharFrequencyReport, FileEntry};
+use pad::{Alignment, PadStr};
+use rayon::prelude::*;
+use std::collections::HashMap;
+use std::error::Error;
+
+/// Count how often each letter occurs in a text (case-insensitive).
+pub fn count_letters(text: &str) -> HashMap<char, usize> {
+  let mut counts = HashMap::new();
+
+  for character in text.chars().filter(|c| c.is_alphabetic()) {
+    for lowercase in character.to_lowercase() {
+      *counts.entry(lowercase).or_insert(0) += 1;
+    }
+  }
+
+  counts
+}
+
+/// Count the letters of all files in parallel and merge the counts.
+pub fn count_file_letters(files: &[FileEntry]) -> HashMap<char, usize> {
+  files
+    .par_iter()
+    .filter_map(|file| file.content.as_str().map(count_letters))
+    .reduce(HashMap::new, |mut merged, counts| {
+      for (letter, count) in counts {
+        *merged.entry(letter).or_insert(0) += count;
+      }
+      merged
+    })
+}
+
+/// Parse a reference table with a letter and its relative frequency
+/// in percent per line, separated by a tab.
+pub fn parse_reference_table(
+  tsv: &str,
+) -> Result<Vec<(char, f64)>, Box<dyn Error>> {
+  tsv
+    .lines()
+    .filter(|line| !line.trim().is_empty())
+    .map(|line| {
+      let (letter, frequency) = line
+        .split_once('\t')
+        .ok_or_else(|| format!("Invalid reference table line: {line}"))?;
+      let mut letters = letter.chars();
+      match (letters.next(), letters.next()) {
+        (Some(letter), None) => Ok((letter, frequency.trim().parse()?)),
+        _ => Err(format!("Invalid reference table letter: {letter}").into()),
+      }
+    })
+    .collect()
+}
+
+/// Compare letter counts with a reference distribution.
+///
+/// Letters which are not part of the reference table are only counted
+/// as `other_letters` and don't influence the chi-squared score.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::char_frequency::{compare_char_frequency, count_letters};
+///
+/// let reference = vec![('a', 50.0), ('b', 50.0)];
+/// let report = compare_char_frequency(&count_letters("aaab!"), &reference);
+///
+/// assert_eq!(report.total_letters, 4);
+/// // (3 - 2)² / 2 + (1 - 2)² / 2
+/// assert_eq!(report.chi_squared, 1.0);
+/// ```
+pub fn compare_char_frequency(
+  counts: &HashMap<char, usize>,
+  reference: &[(char, f64)],
+) -> CharFrequencyReport {
+  let reference_sum: f64 = reference.iter().map(|(_, freq)| freq).sum();
+  let total_letters: usize = reference
+    .iter()
+    .map(|(letter, _)| counts.get(letter).copied().unwrap_or(0))
+    .sum();
+  let other_letters = counts.values().sum::<usize>() - total_letters;
+
+  let mut chi_squared = 0.0;
+  let letters = reference
+    .iter()
+    .map(|(letter, frequency)| {
+      let count = counts.get(letter).copied().unwrap_or(0);
+      let expected_percentage = frequency / reference_sum * 100.0;
+      let percentage = if total_letters == 0 {
+        0.0
+      } else {
+        count as f64 / total_letters as f64 * 100.0
+      };
+
+      let expected_count = expected_percentage / 100.0 * total_letters as f64;
+      if expected_count > 0.0 {
+        chi_squared += (count as f64 - expected_count).powi(2) / expected_count;
+      }
+
+      CharFrequencyItem {
+        letter: *letter,
+        count,
+        percentage,
+        expected_percentage,
+      }
+    })
+    .collect();
+
+  CharFrequencyReport {
+    letters,
+    total_letters,
+    other_letters,
+    chi_squared,
+  }
+}
+
+/// Format a character frequency report as a table.
+pub fn format_char_frequency(report: &CharFrequencyReport) -> String {
+  let count_width = report
+    .letters
+    .iter()
+    .map(|item| item.count.to_string().len())
+    .max()
+    .unwrap_or(0)
+    .max("Count".len());
+  let columns = ["Observed", "Expected", "Deviation"];
+
+  let mut result = format!(
+    "Letter  {}  {}\n",
+    "Count".pad_to_width_with_alignment(count_width, Alignment::Right),
+    columns.join("  ")
+  );
+
+  for item in &report.letters {
+    let values = [
+      format!("{:.2}%", item.percentage),
+      format!("{:.2}%", item.expected_percentage),
+      format!("{:+.2}", item.percentage - item.expected_percentage),
+    ];
+    result += &format!(
+      "{}  {}  {}\n",
+      item
+        .letter
+        .to_string()
+        .pad_to_width_with_alignment("Letter".len(), Alignment::Right),
+      item
+        .count
+        .to_string()
+        .pad_to_width_with_alignment(count_width, Alignment::Right),
+      values
+        .iter()
+        .zip(columns)
+        .map(|(value, column)| {
+          value.pad_to_width_with_alignment(column.len(), Alignment::Right)
+        })
+        .collect::<Vec<_>>()
+        .join("  "),
+    );
+  }
+
+  result += &format!(
+    "\nChi-squared: {:.2} ({} letters, {} other letters)\n",
+    report.chi_squared, report.total_letters, report.other_letters
+  );
+
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::language::Language;
+
+  #[test]
+  fn test_count_letters() {
+    let counts = count_letters("Aa b, Ä! 42");
+    let expected: HashMap<char, usize> =
+      [('a', 2), ('b', 1), ('ä', 1)].into_iter().collect();
+
+    assert_eq!(counts, expected);
+  }
+
+  #[test]
+  fn test_parse_bundled_reference_tables() {
+    for language in Language::ALL {
+      if let Some(tsv) = language.character_frequencies() {
+        let table = parse_reference_table(tsv).unwrap();
+        let sum: f64 = table.iter().map(|(_, freq)| freq).sum();
+        assert!((sum - 100.0).abs() < 0.1, "Invalid table for {language}");
+      }
+    }
+    assert!(parse_reference_table("ab\t1.0").is_err());
+  }
+
+  #[test]
+  fn test_compare_char_frequency_prefers_matching_language() {
+    let text = "It was a bright cold day in April, \
+      and the clocks were striking thirteen.";
+    let counts = count_letters(text);
+    let score = |language: Language| {
+      let tsv = language.character_frequencies().unwrap();
+      let reference = parse_reference_table(tsv).unwrap();
+      compare_char_frequency(&counts, &reference).chi_squared
+    };
+
+    assert!(score(Language::English) < score(Language::German));
+  }
+
+  #[test]
+  fn test_compare_char_frequency_empty() {
+    let report = compare_char_frequency(&HashMap::new(), &[('a', 100.0)]);
+
+    assert_eq!(report.total_letters, 0);
+    assert_eq!(report.chi_squared, 0.0);
+    assert_eq!(report.letters[0].percentage, 0.0);
+  }
+}
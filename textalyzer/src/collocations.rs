@@ -0,0 +1,212 @@
+use crate::frequency::{generate_file_frequency_map, FrequencyOptions};
+use crate::significance::log_likelihood;
+use crate::types::{CollocationItem, CollocationMeasure, FileEntry};
+use pad::{Alignment, PadStr};
+use std::collections::{HashMap, HashSet};
+
+/// Score all word pairs of a bigram frequency map
+/// which occur at least `min_count` times.
+///
+/// The counts of the single words are the number of bigrams
+/// which start or end with them, so that the contingency tables stay
+/// consistent even though bigrams don't span sentence boundaries.
+/// Pairs with a stop word are left out.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashSet;
+/// use textalyzer::frequency::{
+///   generate_frequency_map_with_options, FrequencyOptions,
+/// };
+/// use textalyzer::collocations::score_collocations;
+/// use textalyzer::types::CollocationMeasure;
+///
+/// let bigrams = generate_frequency_map_with_options(
+///   "Big Brother is watching. Big Brother is here. A big dog is here.",
+///   &FrequencyOptions {
+///     ngram: 2,
+///     ..Default::default()
+///   },
+/// );
+/// let collocations = score_collocations(
+///   &bigrams,
+///   CollocationMeasure::Pmi,
+///   2,
+///   &HashSet::new(),
+/// );
+///
+/// assert_eq!(collocations[0].words, ["big", "brother"]);
+/// ```
+pub fn score_collocations(
+  bigrams: &HashMap<String, i32>,
+  measure: CollocationMeasure,
+  min_count: i32,
+  stop_words: &HashSet<String>,
+) -> Vec<CollocationItem> {
+  let mut first_counts: HashMap<&str, i32> = HashMap::new();
+  let mut second_counts: HashMap<&str, i32> = HashMap::new();
+  let mut total = 0;
+
+  let pairs: Vec<(&str, &str, i32)> = bigrams
+    .iter()
+    .filter_map(|(bigram, &count)| {
+      let (first, second) = bigram.split_once(' ')?;
+      *first_counts.entry(first).or_insert(0) += count;
+      *second_counts.entry(second).or_insert(0) += count;
+      total += count;
+      Some((first, second, count))
+    })
+    .collect();
+  let total = total as f64;
+
+  let mut collocations: Vec<CollocationItem> = pairs
+    .into_iter()
+    .filter(|(first, second, count)| {
+      *count >= min_count
+        && !stop_words.contains(*first)
+        && !stop_words.contains(*second)
+    })
+    .map(|(first, second, count)| {
+      let pair = count as f64;
+      let first_count = first_counts[first] as f64;
+      let second_count = second_counts[second] as f64;
+      let score = match measure {
+        CollocationMeasure::Pmi => {
+          (pair * total / (first_count * second_count)).log2()
+        }
+        CollocationMeasure::LogLikelihood => log_likelihood(
+          pair,
+          first_count - pair,
+          second_count - pair,
+          total - first_count - second_count + pair,
+        ),
+      };
+      CollocationItem {
+        words: vec![first.to_string(), second.to_string()],
+        count,
+        score,
+      }
+    })
+    .collect();
+
+  collocations.sort_by(|a, b| {
+    b.score
+      .total_cmp(&a.score)
+      .then(b.count.cmp(&a.count))
+      .then(a.words.cmp(&b.words))
+  });
+  collocations
+}
+
+/// Find the collocations of all files.
+pub fn find_file_collocations(
+  files: &[FileEntry],
+  options: &FrequencyOptions,
+  measure: CollocationMeasure,
+  min_count: i32,
+) -> Vec<CollocationItem> {
+  // Stop words are only removed after counting,
+  // as they are part of the contingency tables
+  let bigram_options = FrequencyOptions {
+    ngram: 2,
+    stop_words: HashSet::new(),
+    ..options.clone()
+  };
+  let bigrams = generate_file_frequency_map(files, &bigram_options);
+  score_collocations(&bigrams, measure, min_count, &options.stop_words)
+}
+
+/// Format collocations as a table.
+pub fn format_collocations(collocations: &[CollocationItem]) -> String {
+  let pairs: Vec<String> = collocations
+    .iter()
+    .map(|collocation| collocation.words.join(" "))
+    .collect();
+  let pair_width = pairs
+    .iter()
+    .map(|pair| pair.chars().count())
+    .max()
+    .unwrap_or(0);
+  let count_width = collocations
+    .iter()
+    .map(|collocation| collocation.count.to_string().len())
+    .max()
+    .unwrap_or(0);
+
+  let mut result = String::new();
+  for (collocation, pair) in collocations.iter().zip(pairs) {
+    result += &format!(
+      "{}  {}  {:.2}\n",
+      pair.pad_to_width_with_alignment(pair_width, Alignment::Left),
+      collocation
+        .count
+        .to_string()
+        .pad_to_width_with_alignment(count_width, Alignment::Right),
+      collocation.score,
+    );
+  }
+
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn bigrams(pairs: &[(&str, i32)]) -> HashMap<String, i32> {
+    pairs
+      .iter()
+      .map(|(pair, count)| (pair.to_string(), *count))
+      .collect()
+  }
+
+  #[test]
+  fn test_score_collocations() {
+    let bigrams = bigrams(&[
+      ("new york", 5),
+      ("new car", 1),
+      ("the car", 8),
+      ("the new", 4),
+      ("york is", 2),
+    ]);
+    let stop_words: HashSet<String> = ["the".to_string()].into();
+
+    let pmi =
+      score_collocations(&bigrams, CollocationMeasure::Pmi, 2, &stop_words);
+    let pairs: Vec<String> =
+      pmi.iter().map(|item| item.words.join(" ")).collect();
+    assert_eq!(pairs, ["york is", "new york"]);
+    // log₂(2 · 20 / (2 · 2))
+    assert_eq!(pmi[0].score, 10f64.log2());
+
+    let log_likelihood = score_collocations(
+      &bigrams,
+      CollocationMeasure::LogLikelihood,
+      1,
+      &HashSet::new(),
+    );
+    assert_eq!(log_likelihood[0].words, ["new", "york"]);
+  }
+
+  #[test]
+  fn test_format_collocations() {
+    let collocations = vec![
+      CollocationItem {
+        words: vec!["big".to_string(), "brother".to_string()],
+        count: 12,
+        score: 80.5,
+      },
+      CollocationItem {
+        words: vec!["new".to_string(), "york".to_string()],
+        count: 3,
+        score: 7.25,
+      },
+    ];
+
+    assert_eq!(
+      format_collocations(&collocations),
+      "big brother  12  80.50\nnew york      3  7.25\n"
+    );
+  }
+}
@@ -0,0 +1,238 @@
+use crate::segmenter::detect_cjk_language;
+use crate::tokenizer::{tokenize, word_spans};
+use crate::types::{ConcordanceItem, FileEntry, TokenizerOptions};
+use pad::{Alignment, PadStr};
+use rayon::prelude::*;
+use unicode_width::UnicodeWidthStr;
+
+/// Collapse all whitespace, including line breaks, into single spaces.
+fn collapse_whitespace(text: &str) -> String {
+  text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Find all occurrences of a word or phrase in a text
+/// with up to `context` words before and after them.
+///
+/// The text and the query are split into words like in
+/// `generate_frequency_map`, so every counted word can be found
+/// and matches are case-insensitive.
+/// Unless the language is set, the query is split with the dictionary
+/// for the language of Chinese characters detected in the text.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::concordance::find_concordances;
+/// use textalyzer::types::TokenizerOptions;
+///
+/// let hits = find_concordances(
+///   "a.txt",
+///   "It was a bright cold day.\nThe clocks were striking thirteen.",
+///   "the clocks",
+///   2,
+///   &TokenizerOptions::default(),
+/// );
+///
+/// assert_eq!(hits[0].line, 2);
+/// assert_eq!(hits[0].left, "cold day.");
+/// assert_eq!(hits[0].keyword, "The clocks");
+/// assert_eq!(hits[0].right, "were striking");
+/// ```
+pub fn find_concordances(
+  path: &str,
+  text: &str,
+  query: &str,
+  context: usize,
+  options: &TokenizerOptions,
+) -> Vec<ConcordanceItem> {
+  let options = &TokenizerOptions {
+    language: options.language.or_else(|| Some(detect_cjk_language(text))),
+    ..*options
+  };
+  let query_words = tokenize(query, options);
+  if query_words.is_empty() {
+    return Vec::new();
+  }
+
+  let spans = word_spans(text, options);
+  let words: Vec<String> =
+    spans.iter().map(|(_, word)| word.to_lowercase()).collect();
+  let span_end = |idx: usize| spans[idx].0 + spans[idx].1.len();
+
+  let mut line = 1;
+  let mut line_counted_until = 0;
+
+  words
+    .windows(query_words.len())
+    .enumerate()
+    .filter(|(_, window)| *window == query_words.as_slice())
+    .map(|(idx, _)| {
+      let last = idx + query_words.len() - 1;
+      let start = spans[idx].0;
+      let end = span_end(last);
+
+      line += text[line_counted_until..start].matches('\n').count();
+      line_counted_until = start;
+
+      let left_start = spans[idx.saturating_sub(context)].0;
+      let right_end = span_end((last + context).min(spans.len() - 1));
+
+      ConcordanceItem {
+        path: path.to_string(),
+        line,
+        left: collapse_whitespace(&text[left_start..start]),
+        keyword: collapse_whitespace(&text[start..end]),
+        right: collapse_whitespace(&text[end..right_end]),
+      }
+    })
+    .collect()
+}
+
+/// Find all occurrences of a word or phrase in all files
+/// like `find_concordances`.
+pub fn find_file_concordances(
+  files: &[FileEntry],
+  query: &str,
+  context: usize,
+  options: &TokenizerOptions,
+) -> Vec<ConcordanceItem> {
+  files
+    .par_iter()
+    .filter_map(|file| {
+      let text = file.content.as_str()?;
+      Some(find_concordances(&file.name, text, query, context, options))
+    })
+    .flatten()
+    .collect()
+}
+
+/// Format occurrences as lines with their position,
+/// aligned on the keyword.
+pub fn format_concordances(items: &[ConcordanceItem]) -> String {
+  let positions: Vec<String> = items
+    .iter()
+    .map(|item| format!("{}:{}", item.path, item.line))
+    .collect();
+  let position_width = positions
+    .iter()
+    .map(|position| UnicodeWidthStr::width(position.as_str()))
+    .max()
+    .unwrap_or(0);
+  let left_width = items
+    .iter()
+    .map(|item| UnicodeWidthStr::width(item.left.as_str()))
+    .max()
+    .unwrap_or(0);
+
+  let mut result = String::new();
+  for (item, position) in items.iter().zip(positions) {
+    // Keep punctuation like "," attached to the keyword
+    let separator = match item.right.chars().next() {
+      Some(character) if character.is_alphanumeric() => " ",
+      _ => "",
+    };
+    let line = format!(
+      "{}  {} {}{separator}{}",
+      position.pad_to_width_with_alignment(position_width, Alignment::Left),
+      item
+        .left
+        .pad_to_width_with_alignment(left_width, Alignment::Right),
+      item.keyword,
+      item.right,
+    );
+    result += line.trim_end();
+    result += "\n";
+  }
+
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn keywords_and_lines(text: &str, query: &str) -> Vec<(String, usize)> {
+    find_concordances("a.txt", text, query, 1, &TokenizerOptions::default())
+      .into_iter()
+      .map(|item| (item.keyword, item.line))
+      .collect()
+  }
+
+  #[test]
+  fn test_find_concordances() {
+    let text = "Big Brother\nis watching you.\n\nBIG brother again";
+
+    assert_eq!(
+      keywords_and_lines(text, "big brother"),
+      vec![
+        ("Big Brother".to_string(), 1),
+        ("BIG brother".to_string(), 4)
+      ]
+    );
+    assert_eq!(
+      keywords_and_lines(text, "brother is"),
+      vec![("Brother is".to_string(), 1)]
+    );
+    assert!(keywords_and_lines(text, "sister").is_empty());
+    assert!(keywords_and_lines(text, "!").is_empty());
+  }
+
+  #[test]
+  fn test_find_concordances_uses_histogram_tokenization() {
+    // "don't" is one word, so "don" must not match
+    assert!(keywords_and_lines("I don't know", "don").is_empty());
+    assert_eq!(
+      keywords_and_lines("I don't know", "don't"),
+      vec![("don't".to_string(), 1)]
+    );
+  }
+
+  #[test]
+  fn test_find_concordances_cjk_language_of_text() {
+    // Without kana the query alone would be detected as Chinese,
+    // where "中国" is one word instead of "中" and "国"
+    assert_eq!(
+      keywords_and_lines("私は中国に行きました", "中国"),
+      vec![("中国".to_string(), 1)]
+    );
+  }
+
+  #[test]
+  fn test_find_concordances_context() {
+    let hits = find_concordances(
+      "a.txt",
+      "one two three four five",
+      "three",
+      5,
+      &TokenizerOptions::default(),
+    );
+
+    assert_eq!(hits[0].left, "one two");
+    assert_eq!(hits[0].right, "four five");
+  }
+
+  #[test]
+  fn test_format_concordances() {
+    let items = vec![
+      ConcordanceItem {
+        path: "a.txt".to_string(),
+        line: 1,
+        left: "the".to_string(),
+        keyword: "war".to_string(),
+        right: "is over".to_string(),
+      },
+      ConcordanceItem {
+        path: "a.txt".to_string(),
+        line: 12,
+        left: "a long".to_string(),
+        keyword: "war".to_string(),
+        right: ", again".to_string(),
+      },
+    ];
+
+    assert_eq!(
+      format_concordances(&items),
+      "a.txt:1      the war is over\na.txt:12  a long war, again\n"
+    );
+  }
+}
@@ -0,0 +1,250 @@
+use crate::frequency::generate_frequency_map;
+use crate::output::format_table;
+use crate::tokenizer::tokenize;
+use crate::types::{FileEntry, LexicalDiversity, TokenizerOptions};
+use rayon::prelude::*;
+use std::collections::{HashMap, HashSet};
+
+/// Type-token ratio at which a factor of the MTLD is complete
+/// (McCarthy & Jarvis, 2010)
+const MTLD_THRESHOLD: f64 = 0.72;
+
+/// Average type-token ratio of all windows with the given number of words.
+/// Texts which are shorter than the window get their plain type-token ratio.
+fn moving_average_ttr(tokens: &[String], window: usize) -> f64 {
+  if tokens.is_empty() {
+    return 0.0;
+  }
+  if window == 0 || tokens.len() <= window {
+    let types: HashSet<&String> = tokens.iter().collect();
+    return types.len() as f64 / tokens.len() as f64;
+  }
+
+  let mut counts: HashMap<&str, usize> = HashMap::new();
+  for token in &tokens[..window] {
+    *counts.entry(token).or_insert(0) += 1;
+  }
+  let mut types_sum = counts.len();
+
+  for idx in window..tokens.len() {
+    let removed = tokens[idx - window].as_str();
+    if let Some(count) = counts.get_mut(removed) {
+      *count -= 1;
+      if *count == 0 {
+        counts.remove(removed);
+      }
+    }
+    *counts.entry(&tokens[idx]).or_insert(0) += 1;
+    types_sum += counts.len();
+  }
+
+  let windows = tokens.len() - window + 1;
+  types_sum as f64 / (windows * window) as f64
+}
+
+/// Number of MTLD factors, i.e. sequences of words
+/// whose type-token ratio drops to the threshold,
+/// including the partial factor of the remaining words.
+fn mtld_factors<'a>(tokens: impl Iterator<Item = &'a String>) -> f64 {
+  let mut factors = 0.0;
+  let mut types = HashSet::new();
+  let mut count = 0;
+
+  for token in tokens {
+    types.insert(token);
+    count += 1;
+    if types.len() as f64 / count as f64 <= MTLD_THRESHOLD {
+      factors += 1.0;
+      types.clear();
+      count = 0;
+    }
+  }
+
+  if count > 0 {
+    let ttr = types.len() as f64 / count as f64;
+    factors += (1.0 - ttr) / (1.0 - MTLD_THRESHOLD);
+  }
+
+  factors
+}
+
+/// Measure of textual lexical diversity:
+/// The average length of word sequences which keep a type-token ratio
+/// above the threshold, averaged over a forward and a backward pass.
+/// Undefined for texts without any repeated words.
+fn mtld(tokens: &[String]) -> Option<f64> {
+  let forward = mtld_factors(tokens.iter());
+  let backward = mtld_factors(tokens.iter().rev());
+  if forward == 0.0 || backward == 0.0 {
+    return None;
+  }
+
+  let length = tokens.len() as f64;
+  Some((length / forward + length / backward) / 2.0)
+}
+
+/// Calculate vocabulary richness and lexical diversity metrics of a text.
+///
+/// Counts of words, hapax and dis legomena, Yule's K,
+/// and Honoré's statistic are derived from the frequency map.
+/// MATTR and MTLD also depend on the order of the words.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::diversity::calculate_diversity;
+///
+/// let diversity = calculate_diversity("The cat saw the other cat.", 50);
+///
+/// assert_eq!(diversity.tokens, 6);
+/// assert_eq!(diversity.types, 4);
+/// assert_eq!(diversity.hapax_legomena, 2);
+/// assert_eq!(diversity.dis_legomena, 2);
+/// ```
+pub fn calculate_diversity(text: &str, window: usize) -> LexicalDiversity {
+  let freq_map = generate_frequency_map(text);
+  let tokens = tokenize(text, &TokenizerOptions::default());
+
+  let token_count: usize = freq_map.values().map(|&count| count as usize).sum();
+  let type_count = freq_map.len();
+  let count_with_frequency = |frequency: i32| {
+    freq_map
+      .values()
+      .filter(|&&count| count == frequency)
+      .count()
+  };
+  let hapax_legomena = count_with_frequency(1);
+  let dis_legomena = count_with_frequency(2);
+
+  let n = token_count as f64;
+  let squares_sum: f64 =
+    freq_map.values().map(|&count| (count as f64).powi(2)).sum();
+  let yules_k = if token_count == 0 {
+    0.0
+  } else {
+    10_000.0 * (squares_sum - n) / n.powi(2)
+  };
+  let honores_r = if token_count == 0 || hapax_legomena == type_count {
+    None
+  } else {
+    Some(100.0 * n.ln() / (1.0 - hapax_legomena as f64 / type_count as f64))
+  };
+
+  LexicalDiversity {
+    tokens: token_count,
+    types: type_count,
+    type_token_ratio: if token_count == 0 {
+      0.0
+    } else {
+      type_count as f64 / n
+    },
+    moving_average_ttr: moving_average_ttr(&tokens, window),
+    mtld: mtld(&tokens),
+    yules_k,
+    honores_r,
+    hapax_legomena,
+    dis_legomena,
+  }
+}
+
+/// Calculate the lexical diversity of each file in parallel.
+pub fn calculate_file_diversity(
+  files: &[FileEntry],
+  window: usize,
+) -> Vec<(String, LexicalDiversity)> {
+  files
+    .par_iter()
+    .filter_map(|file| {
+      let text = file.content.as_str()?;
+      Some((file.name.clone(), calculate_diversity(text, window)))
+    })
+    .collect()
+}
+
+/// Format the lexical diversity of a file into a string.
+pub fn format_diversity(name: &str, diversity: &LexicalDiversity) -> String {
+  let optional = |value: Option<f64>| match value {
+    Some(value) => format!("{value:.2}"),
+    None => "-".to_string(),
+  };
+  let rows = [
+    ("Tokens", diversity.tokens.to_string()),
+    ("Types", diversity.types.to_string()),
+    (
+      "Type-token ratio",
+      format!("{:.4}", diversity.type_token_ratio),
+    ),
+    (
+      "Moving-average TTR",
+      format!("{:.4}", diversity.moving_average_ttr),
+    ),
+    ("MTLD", optional(diversity.mtld)),
+    ("Yule's K", format!("{:.2}", diversity.yules_k)),
+    ("Honoré's R", optional(diversity.honores_r)),
+    ("Hapax legomena", diversity.hapax_legomena.to_string()),
+    ("Dis legomena", diversity.dis_legomena.to_string()),
+  ];
+
+  format_table(name, &rows)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn tokens(text: &str) -> Vec<String> {
+    tokenize(text, &TokenizerOptions::default())
+  }
+
+  #[test]
+  fn test_calculate_diversity() {
+    let diversity = calculate_diversity("a b a c a b d", 50);
+
+    assert_eq!(diversity.tokens, 7);
+    assert_eq!(diversity.types, 4);
+    assert_eq!(diversity.type_token_ratio, 4.0 / 7.0);
+    assert_eq!(diversity.moving_average_ttr, 4.0 / 7.0);
+    assert_eq!(diversity.hapax_legomena, 2);
+    assert_eq!(diversity.dis_legomena, 1);
+    // 10⁴ · (3² + 2² + 1 + 1 − 7) / 7²
+    assert_eq!(diversity.yules_k, 10_000.0 * 8.0 / 49.0);
+    assert_eq!(diversity.honores_r, Some(100.0 * 7f64.ln() / 0.5));
+  }
+
+  #[test]
+  fn test_calculate_diversity_empty() {
+    let diversity = calculate_diversity("", 50);
+
+    assert_eq!(diversity.tokens, 0);
+    assert_eq!(diversity.type_token_ratio, 0.0);
+    assert_eq!(diversity.moving_average_ttr, 0.0);
+    assert_eq!(diversity.mtld, None);
+    assert_eq!(diversity.honores_r, None);
+  }
+
+  #[test]
+  fn test_moving_average_ttr() {
+    // Windows: "a b" (2 types), "b a" (2), "a a" (1)
+    assert_eq!(moving_average_ttr(&tokens("a b a a"), 2), 5.0 / 6.0);
+  }
+
+  #[test]
+  fn test_mtld() {
+    // The TTR drops to 0.5 at the second "a",
+    // and the remaining "b" is a partial factor of 0
+    assert_eq!(mtld_factors(tokens("a a b").iter()), 1.0);
+    assert_eq!(mtld(&tokens("a a a a")), Some(2.0));
+    assert_eq!(mtld(&tokens("all words differ")), None);
+  }
+
+  #[test]
+  fn test_format_diversity() {
+    let formatted =
+      format_diversity("a.txt", &calculate_diversity("all words differ", 50));
+
+    assert!(formatted.starts_with("a.txt\n  Tokens  "));
+    assert!(formatted
+      .lines()
+      .any(|line| line.starts_with("  MTLD ") && line.ends_with(" -")));
+  }
+}
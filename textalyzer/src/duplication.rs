@@ -1,15 +1,37 @@
 use crate::file_utils::merge_file_lines;
-use crate::types::{FileEntry, MappedContent};
+use crate::types::{Duplication, FileEntry};
 use rayon::prelude::*;
-use std::collections::{HashMap, HashSet};
-use std::sync::{Arc, Mutex};
+use std::collections::HashMap;
+
+/// Join lines with the leading whitespace they all share removed.
+pub(crate) fn remove_common_indent<S: AsRef<str>>(lines: &[S]) -> String {
+  // Determine the minimum leading-whitespace width
+  let min_indent = lines
+    .iter()
+    .filter_map(|l| {
+      let l = l.as_ref();
+      let trimmed = l.trim_start();
+      if trimmed.is_empty() {
+        None
+      } else {
+        Some(l.len() - trimmed.len()) // number of leading white-space bytes
+      }
+    })
+    .min()
+    .unwrap_or(0);
+
+  // Re-build block with that common indent removed
+  lines
+    .iter()
+    .map(|l| l.as_ref().get(min_indent..).unwrap_or(l.as_ref()))
+    .collect::<Vec<&str>>()
+    .join("\n")
+}
 
 /// Find single-line duplications in a given text.
 /// Works with both memory mapped files and regular string content.
 /// Only includes lines with more than 5 characters after trimming.
-pub fn find_duplicate_lines(
-  files: Vec<FileEntry>,
-) -> Vec<(String, Vec<(String, u32)>)> {
+pub fn find_duplicate_lines(files: Vec<FileEntry>) -> Vec<Duplication> {
   let lines = merge_file_lines(
     &|line: &&str| line.trim().len() > 5,
     files, //
@@ -21,7 +43,11 @@ pub fn find_duplicate_lines(
     let line_count = line_map //
       .entry(&line_entry.content)
       .or_insert_with(Vec::new);
-    line_count.push((line_entry.file_name.clone(), line_entry.line_number));
+    line_count.push((
+      line_entry.file_name.clone(),
+      line_entry.line_number,
+      line_entry.line_number,
+    ));
   }
 
   for (line, line_locations) in line_map {
@@ -39,300 +65,287 @@ pub fn find_duplicate_lines(
   duplications
 }
 
-/// Find duplications across files, utilizing parallel processing.
-///
-/// This function detects sequences of consecutive lines that are duplicated
-/// across files or within the same file, prioritizing longer sequences.
-/// Captures all duplications, including single-line ones, but they will be
-/// filtered later based on the min_lines parameter.
-/// Empty lines are not counted when determining line count for filtering.
-/// When duplications overlap, only the longest one is kept.
-///
-/// Uses memory mapping for improved performance with large files.
-pub fn find_multi_line_duplications(
-  files: Vec<FileEntry>,
-) -> Vec<(String, Vec<(String, u32)>)> {
-  // Type definitions to reduce complexity
-  type Location = (String, u32);
-  type LineIndex = HashMap<String, Vec<Location>>;
-  type BlocksMap = HashMap<String, Vec<Location>>;
-  type SharedLineIndex = Arc<Mutex<LineIndex>>;
-  type SharedBlocksMap = Arc<Mutex<BlocksMap>>;
-
-  // Create a mapping of file lines for each file
-  let file_lines_map: HashMap<String, Vec<String>> = files
+/// Non-empty lines of a file
+struct FileLines<'a> {
+  name: &'a str,
+  lines: Vec<&'a str>,
+  /// Line number of each non-empty line
+  line_numbers: Vec<u32>,
+}
+
+fn split_file_lines(file: &FileEntry) -> FileLines<'_> {
+  let lines: Vec<&str> = file
+    .content
+    .as_str()
+    .map(|content| content.lines().collect())
+    .unwrap_or_default();
+  let line_numbers = lines
     .iter()
-    .map(|f| {
-      // Get the lines from either mapped or string content
-      let lines: Vec<String> = match &f.content {
-        MappedContent::Mapped(mmap) => {
-          if let Ok(content) = std::str::from_utf8(mmap) {
-            content.lines().map(String::from).collect()
-          } else {
-            Vec::new()
-          }
-        }
-        MappedContent::String(content) => {
-          content.lines().map(String::from).collect()
-        }
-      };
-      (f.name.clone(), lines)
-    })
+    .enumerate()
+    .filter(|(_, line)| !line.trim().is_empty())
+    .map(|(idx, _)| idx as u32 + 1)
     .collect();
 
-  // Create initial line index - map from line content to locations
-  // Using a shared hash map for concurrent access
-  let line_index: SharedLineIndex = Arc::new(Mutex::new(HashMap::new()));
-
-  // Build the initial index of duplicate lines in parallel
-  files.par_iter().for_each(|file_entry| {
-    if let Some(file_lines) = file_lines_map.get(&file_entry.name) {
-      let mut local_entries = Vec::new();
-
-      // Process each line in the file and store entries in a local collection
-      for (i, line) in file_lines.iter().enumerate() {
-        let trimmed = line.trim();
-        if !trimmed.is_empty() {
-          local_entries.push((
-            trimmed.to_string(), // key used for matching
-            (file_entry.name.clone(), (i + 1) as u32),
-          ));
-        }
-      }
+  FileLines {
+    name: &file.name,
+    lines,
+    line_numbers,
+  }
+}
+
+/// Suffix array of `ids` built by prefix doubling.
+/// Ranks are sorted in parallel and it stops as soon as all suffixes
+/// are distinct, which takes as many rounds as the logarithm
+/// of the longest repeated sequence.
+fn suffix_array(ids: &[u32]) -> Vec<u32> {
+  let length = ids.len();
+  let mut suffixes: Vec<u32> = (0..length as u32).collect();
+  let mut rank: Vec<u32> = ids.to_vec();
+  let mut next_rank = vec![0u32; length];
+  let mut step = 1;
+
+  loop {
+    // Rank of the suffix `step` positions later, 0 if there is none
+    let key = |idx: u32| {
+      let idx = idx as usize;
+      let second = rank.get(idx + step).map_or(0, |rank| rank + 1);
+      (rank[idx], second)
+    };
+    suffixes.par_sort_unstable_by_key(|&idx| key(idx));
 
-      // Update the shared line index less frequently
-      let mut index = line_index.lock().unwrap();
-      for (line, location) in local_entries {
-        index.entry(line).or_default().push(location);
+    let mut current = 0;
+    for pair in suffixes.windows(2) {
+      if key(pair[0]) != key(pair[1]) {
+        current += 1;
       }
+      next_rank[pair[1] as usize] = current;
     }
-  });
-
-  // Get the inner value from Arc<Mutex<T>>
-  let raw_line_index = Arc::try_unwrap(line_index)
-    .expect("References to line_index still exist")
-    .into_inner()
-    .expect("Failed to unwrap Mutex");
+    if let Some(&first) = suffixes.first() {
+      next_rank[first as usize] = 0;
+    }
+    std::mem::swap(&mut rank, &mut next_rank);
 
-  // Only keep lines that appear in multiple locations (duplicates)
-  let duplicate_lines: HashMap<String, Vec<(String, u32)>> = raw_line_index
-    .into_iter()
-    .filter(|(_, locations)| locations.len() > 1)
-    .collect();
+    if current as usize + 1 >= length {
+      return suffixes;
+    }
+    step *= 2;
+  }
+}
 
-  // For efficiency, only consider lines that appear as duplicates
-  let duplicate_line_set: HashSet<String> =
-    duplicate_lines.keys().cloned().collect();
-
-  // Create a thread-safe container for blocks
-  let blocks_map: SharedBlocksMap = Arc::new(Mutex::new(HashMap::new()));
-
-  // Process each file in parallel
-  files.par_iter().for_each(|file_entry| {
-    let file_name = &file_entry.name;
-    if let Some(file_lines) = file_lines_map.get(file_name) {
-      let file_len = file_lines.len();
-
-      // Local collection to minimize locks
-      let mut local_blocks: HashMap<String, Vec<(String, u32)>> =
-        HashMap::new();
-
-      // For each potential starting position
-      for start_idx in 0..file_len {
-        // Skip if the first line isn't a known duplicate or is empty
-        if start_idx < file_lines.len() {
-          let first_line = &file_lines[start_idx];
-          if !duplicate_line_set.contains(first_line)
-            || first_line.trim().is_empty()
-          {
-            continue;
-          }
-
-          // Get all locations where this first line appears
-          if let Some(locations) = duplicate_lines.get(first_line) {
-            // For each other place this line appears
-            for (other_file, other_line_num) in locations {
-              // Skip if it's the same position we're checking from
-              if other_file == file_name
-                && *other_line_num == (start_idx as u32 + 1)
-              {
-                continue;
-              }
-
-              // Look up the other file's lines
-              if let Some(other_file_lines) = file_lines_map.get(other_file) {
-                let other_start_idx = (*other_line_num - 1) as usize;
-                let other_file_len = other_file_lines.len();
-
-                // Calculate maximum possible match length
-                let max_len = std::cmp::min(
-                  file_len - start_idx,
-                  other_file_len - other_start_idx,
-                );
-
-                // Find how many consecutive lines match
-                let mut match_len = 0;
-                for offset in 0..max_len {
-                  if start_idx + offset < file_lines.len()
-                    && other_start_idx + offset < other_file_lines.len()
-                    && file_lines[start_idx + offset].trim()
-                      == other_file_lines[other_start_idx + offset].trim()
-                  {
-                    match_len += 1;
-                  } else {
-                    break;
-                  }
-                }
-
-                // Only process matches of at least 1 line
-                if match_len >= 1 {
-                  // Slice with the original (indented) lines that form this block
-                  let block_lines =
-                    &file_lines[start_idx..start_idx + match_len];
-
-                  // Determine the minimum leading-whitespace width
-                  let min_indent = block_lines
-                    .iter()
-                    .filter_map(|l| {
-                      let trimmed = l.trim_start();
-                      if trimmed.is_empty() {
-                        None
-                      } else {
-                        Some(l.len() - trimmed.len()) // number of leading white-space bytes
-                      }
-                    })
-                    .min()
-                    .unwrap_or(0);
-
-                  // Re-build block with that common indent removed
-                  let block = block_lines
-                    .iter()
-                    .map(|l| {
-                      if l.len() >= min_indent {
-                        l[min_indent..].to_string()
-                      } else {
-                        l.clone()
-                      }
-                    })
-                    .collect::<Vec<String>>()
-                    .join("\n");
-
-                  // Use our local hash map for faster lookups
-                  let locations = local_blocks.entry(block).or_default();
-
-                  // Add the current file location if not already present
-                  let current_loc = (file_name.clone(), start_idx as u32 + 1);
-                  if !locations.contains(&current_loc) {
-                    locations.push(current_loc);
-                  }
-
-                  // Add the other location if not already present
-                  let other_loc = (other_file.clone(), *other_line_num);
-                  if !locations.contains(&other_loc) {
-                    locations.push(other_loc);
-                  }
-                }
-              }
-            }
-          }
-        }
-      }
+/// Length of the longest common prefix of each suffix
+/// and the one before it in the suffix array (Kasai's algorithm)
+fn longest_common_prefixes(ids: &[u32], suffixes: &[u32]) -> Vec<u32> {
+  let mut rank = vec![0usize; ids.len()];
+  for (idx, &suffix) in suffixes.iter().enumerate() {
+    rank[suffix as usize] = idx;
+  }
 
-      // Merge local blocks into the shared map
-      if !local_blocks.is_empty() {
-        let mut shared_blocks = blocks_map.lock().unwrap();
-        for (block, locations) in local_blocks {
-          let shared_locations = shared_blocks.entry(block).or_default();
-          for loc in locations {
-            if !shared_locations.contains(&loc) {
-              shared_locations.push(loc);
-            }
-          }
-        }
-      }
+  let mut prefixes = vec![0u32; ids.len()];
+  let mut length = 0;
+  for position in 0..ids.len() {
+    if rank[position] == 0 {
+      length = 0;
+      continue;
     }
-  });
+    let previous = suffixes[rank[position] - 1] as usize;
+    while position + length < ids.len()
+      && previous + length < ids.len()
+      && ids[position + length] == ids[previous + length]
+    {
+      length += 1;
+    }
+    prefixes[rank[position]] = length as u32;
+    length = length.saturating_sub(1);
+  }
+  prefixes
+}
 
-  // Get the inner value from Arc<Mutex<T>>
-  let raw_blocks_map = Arc::try_unwrap(blocks_map)
-    .expect("References to blocks_map still exist")
-    .into_inner()
-    .expect("Failed to unwrap Mutex");
-
-  // Convert to Vec and filter basic criteria
-  let mut all_blocks: Vec<(String, Vec<(String, u32)>)> = raw_blocks_map
-    .into_iter()
-    .filter(|(content, _)| {
-      // Keep any block with at least one duplicate and one non-empty line
-      // Final filtering by min_lines will happen in lib.rs
-      content
-        .split('\n')
-        .filter(|line| !line.trim().is_empty())
-        .count()
-        >= 1
-    })
-    .collect();
+/// Maximum number of non-empty lines of a multi-line duplication.
+/// Longer duplications are reported as several blocks,
+/// which limits the work for files with many repeated lines.
+const MAX_BLOCK_LINES: usize = 1000;
+
+/// Positions in an interval of the suffix array,
+/// which are followed by the same `length` lines
+struct Group {
+  length: usize,
+  /// Range of the positions in the suffix array
+  suffixes: std::ops::Range<usize>,
+  /// Smallest position
+  first: usize,
+}
 
-  // Sort by most non-empty lines first, then by length
-  all_blocks.sort_by(|a, b| {
-    // Count non-empty lines in each block
-    let a_lines = a
-      .0
-      .split('\n')
-      .filter(|line| !line.trim().is_empty())
-      .count();
-    let b_lines = b
-      .0
-      .split('\n')
-      .filter(|line| !line.trim().is_empty())
-      .count();
-
-    let line_cmp = b_lines.cmp(&a_lines);
-    if line_cmp == std::cmp::Ordering::Equal {
-      b.0.len().cmp(&a.0.len())
-    } else {
-      line_cmp
+/// Find duplications across files, utilizing parallel processing.
+///
+/// This function detects sequences of consecutive lines that are duplicated
+/// across files or within the same file, prioritizing longer sequences.
+/// Captures all duplications, including single-line ones, but they will be
+/// filtered later based on the min_lines parameter.
+/// Empty lines are skipped when comparing lines
+/// and leading and trailing whitespace is ignored.
+/// When duplications overlap, only the longest one is kept.
+///
+/// All lines are interned to numeric IDs and the IDs of all files
+/// are joined into one sequence with a unique separator after each file.
+/// Every group of positions sharing the same next lines forms an interval
+/// in the suffix array of the sequence, so all repeated sequences
+/// are found in O(n log n) time and O(n) memory,
+/// independent of how often a line like `}` is repeated.
+/// Duplications are at most `MAX_BLOCK_LINES` non-empty lines long.
+///
+/// Uses memory mapping for improved performance with large files.
+pub fn find_multi_line_duplications(files: Vec<FileEntry>) -> Vec<Duplication> {
+  let file_lines: Vec<FileLines> =
+    files.par_iter().map(split_file_lines).collect();
+
+  // Separators get the IDs 0..files.len(),
+  // so that no duplication can continue into the next file
+  let mut line_ids: HashMap<&str, u32> = HashMap::new();
+  let mut ids = Vec::new();
+  let mut starts = Vec::with_capacity(file_lines.len());
+  for (file_idx, file) in file_lines.iter().enumerate() {
+    starts.push(ids.len());
+    for &number in &file.line_numbers {
+      let line = file.lines[number as usize - 1].trim();
+      let next_id = (file_lines.len() + line_ids.len()) as u32;
+      ids.push(*line_ids.entry(line).or_insert(next_id));
     }
+    ids.push(file_idx as u32);
+  }
+  drop(line_ids);
+
+  let suffixes = suffix_array(&ids);
+  let prefixes = longest_common_prefixes(&ids, &suffixes);
+
+  // Collect the intervals of the suffix array whose suffixes share
+  // their first `length` lines. Only the range in the suffix array
+  // is stored, so this needs O(n) memory even for files
+  // with many repeated lines. A duplication which continues to the left
+  // is also collected, as the longer one might overlap another one.
+  let mut groups: Vec<Group> = Vec::new();
+  let mut add_group = |length: u32, start: usize, end: usize| {
+    groups.push(Group {
+      length: length as usize,
+      suffixes: start..end,
+      first: suffixes[start..end].iter().copied().min().unwrap_or(0) as usize,
+    });
+  };
+  let mut stack: Vec<(u32, usize)> = vec![(0, 0)];
+  for idx in 1..=suffixes.len() {
+    let prefix = prefixes
+      .get(idx)
+      .map_or(0, |prefix| (*prefix).min(MAX_BLOCK_LINES as u32));
+    let mut left = idx - 1;
+    while let Some(&(length, start)) = stack.last() {
+      if prefix >= length {
+        break;
+      }
+      stack.pop();
+      add_group(length, start, idx);
+      left = start;
+    }
+    if stack.last().is_none_or(|&(length, _)| prefix > length) {
+      stack.push((prefix, left));
+    }
+  }
+  drop(prefixes);
+
+  // Length of the trimmed lines to prefer longer lines
+  // between duplications with the same number of lines
+  let mut text_lengths = Vec::with_capacity(ids.len() + 1);
+  text_lengths.push(0);
+  for file in &file_lines {
+    for &number in &file.line_numbers {
+      let length = file.lines[number as usize - 1].trim().len();
+      text_lengths.push(text_lengths.last().unwrap() + length);
+    }
+    text_lengths.push(*text_lengths.last().unwrap());
+  }
+  let text_length = |position: usize, length: usize| {
+    text_lengths[position + length] - text_lengths[position]
+  };
+
+  // Longest duplications first
+  groups.par_sort_unstable_by(|a, b| {
+    b.length
+      .cmp(&a.length)
+      .then_with(|| {
+        text_length(b.first, b.length).cmp(&text_length(a.first, a.length))
+      })
+      .then(a.first.cmp(&b.first))
   });
 
-  // Process overlapping duplications
-  // This part is not parallelized because it processes items sequentially
-  // based on their sorted order
+  let location = |position: usize| {
+    let file_idx = starts.partition_point(|&start| start <= position) - 1;
+    (file_idx, position - starts[file_idx])
+  };
+
+  let mut used = vec![false; ids.len()];
   let mut result = Vec::new();
-  let mut used_positions: HashMap<(String, u32), usize> = HashMap::new();
-
-  for (content, locations) in all_blocks {
-    let lines_count = content.matches('\n').count() + 1;
-    let mut valid_locations = Vec::new();
-
-    // Check each location for overlap
-    for (file, line_num) in &locations {
-      let end_line = line_num + lines_count as u32 - 1;
-      let mut position_free = true;
-
-      // Fast overlap check - break early
-      for l in *line_num..=end_line {
-        if let Some(idx) = used_positions.get(&(file.clone(), l)) {
-          if *idx < result.len() {
-            position_free = false;
-            break;
-          }
-        }
-      }
 
-      if position_free {
-        valid_locations.push((file.clone(), *line_num));
-        // Mark positions as used
-        for l in *line_num..=end_line {
-          used_positions.insert((file.clone(), l), result.len());
-        }
+  for Group {
+    length,
+    suffixes: range,
+    ..
+  } in groups
+  {
+    // All blocks in `used` are at least as long as this one,
+    // so a block overlapping this one must contain its first or last line
+    let is_free = |used: &[bool], position: usize| {
+      !used[position] && !used[position + length - 1]
+    };
+    let mut candidates: Vec<usize> = suffixes[range]
+      .iter()
+      .map(|position| *position as usize)
+      .filter(|&position| is_free(&used, position))
+      .collect();
+    if candidates.len() < 2 {
+      continue;
+    }
+    candidates.sort_unstable();
+
+    // Copies within the same file may overlap each other
+    let mut free_positions = Vec::with_capacity(candidates.len());
+    for position in candidates {
+      if is_free(&used, position) {
+        used[position..position + length].fill(true);
+        free_positions.push(position);
       }
     }
-
-    // Only keep duplications with at least 2 valid locations
-    if valid_locations.len() >= 2 {
-      result.push((content, valid_locations));
+    if free_positions.len() < 2 {
+      // Release the position again if it isn't a duplication
+      for &position in &free_positions {
+        used[position..position + length].fill(false);
+      }
+      continue;
     }
+
+    // Show the copy with the least indentation inside of the block
+    let content = free_positions
+      .iter()
+      .map(|&position| {
+        let (file_idx, line) = location(position);
+        let file = &file_lines[file_idx];
+        let start = file.line_numbers[line] as usize - 1;
+        let end = file.line_numbers[line + length - 1] as usize;
+        remove_common_indent(&file.lines[start..end])
+      })
+      .min_by_key(|content| content.len())
+      .unwrap_or_default();
+
+    let locations = free_positions
+      .into_iter()
+      .map(|position| {
+        let (file_idx, line) = location(position);
+        let file = &file_lines[file_idx];
+        (
+          file.name.to_string(),
+          file.line_numbers[line],
+          file.line_numbers[line + length - 1],
+        )
+      })
+      .collect();
+    result.push((content, locations));
   }
 
   result
@@ -342,10 +355,6 @@ pub fn find_multi_line_duplications(
 mod tests {
   use super::*;
   use crate::types::{FileEntry, MappedContent};
-  use std::fs::File;
-  use std::io::Write;
-  use std::time::Instant;
-  use tempfile::tempdir;
 
   #[test]
   fn test_find_duplicate_lines() {
@@ -373,9 +382,9 @@ mod tests {
     let expected_duplications = vec![(
       "This is a test.".to_string(),
       vec![
-        ("file1.txt".to_string(), 1),
-        ("file1.txt".to_string(), 3),
-        ("file2.txt".to_string(), 1),
+        ("file1.txt".to_string(), 1, 1),
+        ("file1.txt".to_string(), 3, 3),
+        ("file2.txt".to_string(), 1, 1),
       ],
     )];
 
@@ -431,8 +440,8 @@ mod tests {
       2,
       "Expected 2 locations for 3-line duplication"
     );
-    assert!(locations.contains(&("file1.txt".to_string(), 1)));
-    assert!(locations.contains(&("file2.txt".to_string(), 2)));
+    assert!(locations.contains(&("file1.txt".to_string(), 1, 3)));
+    assert!(locations.contains(&("file2.txt".to_string(), 2, 4)));
 
     // The 2-line duplication should not be present because it's covered
     // by the 3-line duplication at the same starting positions
@@ -484,13 +493,13 @@ mod tests {
       if block == block_a {
         found_block_a = true;
         assert_eq!(locations.len(), 2);
-        assert!(locations.contains(&("file1.txt".to_string(), 1)));
-        assert!(locations.contains(&("file2.txt".to_string(), 2)));
+        assert!(locations.contains(&("file1.txt".to_string(), 1, 3)));
+        assert!(locations.contains(&("file2.txt".to_string(), 2, 4)));
       } else if block == block_b {
         found_block_b = true;
         assert_eq!(locations.len(), 2);
-        assert!(locations.contains(&("file1.txt".to_string(), 5)));
-        assert!(locations.contains(&("file2.txt".to_string(), 6)));
+        assert!(locations.contains(&("file1.txt".to_string(), 5, 6)));
+        assert!(locations.contains(&("file2.txt".to_string(), 6, 7)));
       }
     }
 
@@ -499,59 +508,67 @@ mod tests {
   }
 
   #[test]
-  #[ignore] // This is a benchmark test, run it explicitly
-  fn benchmark_multi_line_duplications() {
-    // Create temporary files with duplications
-    let temp_dir = tempdir().unwrap();
-    let temp_path = temp_dir.path();
-
-    const NUM_FILES: usize = 20;
-    const LINES_PER_FILE: usize = 2000;
-    const DUPLICATED_BLOCKS: usize = 30;
-    const BLOCK_SIZE: usize = 5;
-
-    let mut files = Vec::new();
-
-    // Generate test files with duplicated blocks
-    for i in 0..NUM_FILES {
-      let file_path = temp_path.join(format!("file{}.txt", i));
-      let mut content = String::new();
-
-      for j in 0..LINES_PER_FILE {
-        // Insert duplicated blocks at regular intervals
-        if j % 50 == 0 && j < DUPLICATED_BLOCKS * 50 {
-          let block_id = j / 50;
-          for k in 0..BLOCK_SIZE {
-            content.push_str(&format!(
-              "This is duplicated block {} line {}\n",
-              block_id, k
-            ));
-          }
-        } else {
-          content.push_str(&format!("Unique line {} in file {}\n", j, i));
-        }
-      }
+  fn test_multi_line_duplications_with_repeated_lines() {
+    let content =
+      "}\nfirst();\n}\nsecond();\nthird();\n}\n\nsecond();\nthird();\n";
+    let file = FileEntry {
+      name: "file.txt".to_string(),
+      content: MappedContent::String(content.to_string()),
+    };
 
-      File::create(&file_path)
-        .unwrap()
-        .write_all(content.as_bytes())
-        .unwrap();
-      files.push(file_path);
-    }
+    let duplications = find_multi_line_duplications(vec![file]);
+
+    assert_eq!(
+      duplications[0],
+      (
+        "}\nsecond();\nthird();".to_string(),
+        vec![
+          ("file.txt".to_string(), 3, 5),
+          ("file.txt".to_string(), 6, 9)
+        ],
+      )
+    );
+  }
+
+  #[test]
+  fn test_multi_line_duplications_of_identical_lines() {
+    let file = FileEntry {
+      name: "file.txt".to_string(),
+      content: MappedContent::String("x();\n".repeat(2500)),
+    };
+
+    let duplications = find_multi_line_duplications(vec![file]);
 
-    // Load files - now using memory mapping
-    let file_entries = crate::file_utils::load_files(files).unwrap();
+    let (content, locations) = &duplications[0];
+    assert_eq!(content.lines().count(), MAX_BLOCK_LINES);
+    assert_eq!(
+      locations,
+      &vec![
+        ("file.txt".to_string(), 1, 1000),
+        ("file.txt".to_string(), 1001, 2000)
+      ]
+    );
+  }
 
-    // Measure performance
-    let start = Instant::now();
-    let duplications = find_multi_line_duplications(file_entries);
-    let duration = start.elapsed();
+  #[test]
+  fn test_multi_line_duplications_longer_than_maximum() {
+    let content: String =
+      (0..2500).map(|idx| format!("line {idx}\n")).collect();
+    let file = |name: &str| FileEntry {
+      name: name.to_string(),
+      content: MappedContent::String(content.clone()),
+    };
 
-    println!("Time elapsed: {:?}", duration);
-    println!("Found {} duplications", duplications.len());
+    let duplications = find_multi_line_duplications(vec![file("a"), file("b")]);
 
-    // Verify that we found all duplicated blocks
-    assert_eq!(duplications.len(), DUPLICATED_BLOCKS);
+    let blocks: Vec<(usize, u32)> = duplications
+      .iter()
+      .map(|(content, locations)| (content.lines().count(), locations[0].1))
+      .collect();
+    assert_eq!(blocks.len(), 3);
+    assert!(blocks.contains(&(1000, 1)));
+    assert!(blocks.contains(&(1000, 1001)));
+    assert!(blocks.contains(&(500, 2001)));
   }
 
   #[test]
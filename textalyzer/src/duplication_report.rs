@@ -0,0 +1,302 @@
+use crate::types::{
+  DuplicatedLines, DuplicatedLinesItem, DuplicationReport, FileEntry,
+};
+use pad::{Alignment, PadStr};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+impl DuplicatedLines {
+  /// Count `duplicated_lines` of `lines` and calculate their share
+  pub fn new(lines: usize, duplicated_lines: usize) -> DuplicatedLines {
+    let percentage = if lines == 0 {
+      0.0
+    } else {
+      duplicated_lines as f64 * 100.0 / lines as f64
+    };
+    DuplicatedLines {
+      lines,
+      duplicated_lines,
+      percentage,
+    }
+  }
+}
+
+/// Line numbers of the non-empty lines of each file
+pub fn non_empty_line_numbers(
+  files: &[FileEntry],
+) -> HashMap<String, Vec<u32>> {
+  files
+    .iter()
+    .map(|file| {
+      let numbers = file
+        .content
+        .as_str()
+        .unwrap_or_default()
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, _)| idx as u32 + 1)
+        .collect();
+      (file.name.clone(), numbers)
+    })
+    .collect()
+}
+
+/// Sort by percentage, then by number of duplicated lines
+fn sort_items(items: &mut [DuplicatedLinesItem]) {
+  items.sort_by(|a, b| {
+    b.lines
+      .percentage
+      .total_cmp(&a.lines.percentage)
+      .then(b.lines.duplicated_lines.cmp(&a.lines.duplicated_lines))
+      .then_with(|| a.path.cmp(&b.path))
+  });
+}
+
+/// Deepest directory containing all paths
+fn common_directory<'a>(paths: impl Iterator<Item = &'a str>) -> PathBuf {
+  let mut common: Option<PathBuf> = None;
+  for path in paths {
+    let parent = Path::new(path).parent().unwrap_or(Path::new(""));
+    common = Some(match common {
+      None => parent.to_path_buf(),
+      Some(common) => common
+        .components()
+        .zip(parent.components())
+        .take_while(|(a, b)| a == b)
+        .map(|(a, _)| a)
+        .collect(),
+    });
+  }
+  common.unwrap_or_default()
+}
+
+/// Calculate which share of the non-empty lines of each file
+/// is part of a duplication and sum them up per directory and in total.
+///
+/// `file_lines` contains the non-empty line numbers of each file
+/// (see `non_empty_line_numbers`) and `copies` the path, first line,
+/// and last line of every copy of all duplications.
+/// Copies can span different lines, e.g. if they are formatted differently.
+/// Directories are listed down from the deepest directory containing
+/// all files. Only files and directories with duplicated lines
+/// are listed, sorted by their percentage of duplicated lines.
+///
+/// # Examples
+///
+/// ```rust
+/// use std::collections::HashMap;
+/// use textalyzer::duplication_report::build_report;
+///
+/// let file_lines = HashMap::from([
+///   ("src/a.rs".to_string(), vec![1, 2, 4, 5]),
+///   ("src/b.rs".to_string(), vec![1, 2, 3, 4, 5, 6, 7, 8]),
+/// ]);
+/// let report =
+///   build_report(&file_lines, [("src/a.rs", 2, 4), ("src/b.rs", 7, 8)]);
+///
+/// assert_eq!(report.files[0].path, "src/a.rs");
+/// assert_eq!(report.files[0].lines.percentage, 50.0);
+/// assert_eq!(report.directories[0].path, "src");
+/// assert_eq!(report.total.duplicated_lines, 4);
+/// assert_eq!(report.total.lines, 12);
+/// ```
+pub fn build_report<'a>(
+  file_lines: &HashMap<String, Vec<u32>>,
+  copies: impl IntoIterator<Item = (&'a str, u32, u32)>,
+) -> DuplicationReport {
+  let mut duplicated: HashMap<&str, Vec<bool>> = HashMap::new();
+
+  for (path, first_line, last_line) in copies {
+    let Some(numbers) = file_lines.get(path) else {
+      continue;
+    };
+    let start = numbers.partition_point(|number| *number < first_line);
+    let end = numbers.partition_point(|number| *number <= last_line);
+    if start < end {
+      duplicated
+        .entry(path)
+        .or_insert_with(|| vec![false; numbers.len()])[start..end]
+        .fill(true);
+    }
+  }
+
+  let common = common_directory(file_lines.keys().map(String::as_str));
+  let mut directory_counts: HashMap<&Path, (usize, usize)> = HashMap::new();
+  let mut files = Vec::new();
+  let mut total = (0, 0);
+
+  for (path, numbers) in file_lines {
+    let duplicated_lines = duplicated
+      .get(path.as_str())
+      .map_or(0, |lines| lines.iter().filter(|line| **line).count());
+    total.0 += numbers.len();
+    total.1 += duplicated_lines;
+
+    let directories = Path::new(path).ancestors().skip(1).filter(|directory| {
+      directory.starts_with(&common) && !directory.as_os_str().is_empty()
+    });
+    for directory in directories {
+      let counts = directory_counts.entry(directory).or_default();
+      counts.0 += numbers.len();
+      counts.1 += duplicated_lines;
+    }
+
+    if duplicated_lines > 0 {
+      files.push(DuplicatedLinesItem {
+        path: path.clone(),
+        lines: DuplicatedLines::new(numbers.len(), duplicated_lines),
+      });
+    }
+  }
+
+  let mut directories: Vec<DuplicatedLinesItem> = directory_counts
+    .into_iter()
+    .filter(|(_, (_, duplicated_lines))| *duplicated_lines > 0)
+    .map(|(path, (lines, duplicated_lines))| DuplicatedLinesItem {
+      path: path.display().to_string(),
+      lines: DuplicatedLines::new(lines, duplicated_lines),
+    })
+    .collect();
+
+  sort_items(&mut files);
+  sort_items(&mut directories);
+
+  DuplicationReport {
+    files,
+    directories,
+    total: DuplicatedLines::new(total.0, total.1),
+  }
+}
+
+/// Format a table of duplicated lines per file or directory
+fn format_items(title: &str, items: &[DuplicatedLinesItem]) -> String {
+  let mut result = format!("{title}\n");
+  if items.is_empty() {
+    result += "  -\n";
+    return result;
+  }
+
+  let rows: Vec<[String; 3]> = items
+    .iter()
+    .map(|item| {
+      [
+        format!("{:.2}%", item.lines.percentage),
+        item.lines.duplicated_lines.to_string(),
+        item.lines.lines.to_string(),
+      ]
+    })
+    .collect();
+  let headers = ["Share", "Duplicated", "Lines"];
+  let widths: Vec<usize> = (0..headers.len())
+    .map(|column| {
+      rows
+        .iter()
+        .map(|row| row[column].len())
+        .chain([headers[column].len()])
+        .max()
+        .unwrap_or(0)
+    })
+    .collect();
+
+  let format_row = |values: [&str; 3], path: &str| {
+    let mut row = String::new();
+    for (value, width) in values.iter().zip(&widths) {
+      row += "  ";
+      row += &value.pad_to_width_with_alignment(*width, Alignment::Right);
+    }
+    row + "  " + path + "\n"
+  };
+
+  result += &format_row(headers, "Path");
+  for (row, item) in rows.iter().zip(items) {
+    result += &format_row([&row[0], &row[1], &row[2]], &item.path);
+  }
+  result
+}
+
+/// Format the report as tables of files and directories
+/// sorted by their share of duplicated lines, followed by the total.
+pub fn format_report(report: &DuplicationReport) -> String {
+  format!(
+    "{}\n{}\nTotal: {} of {} lines duplicated ({:.2}%)\n",
+    format_items("Duplicated lines per file", &report.files),
+    format_items("Duplicated lines per directory", &report.directories),
+    report.total.duplicated_lines,
+    report.total.lines,
+    report.total.percentage,
+  )
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn file_lines(files: &[(&str, u32)]) -> HashMap<String, Vec<u32>> {
+    files
+      .iter()
+      .map(|(path, count)| (path.to_string(), (1..=*count).collect()))
+      .collect()
+  }
+
+  #[test]
+  fn test_build_report() {
+    let file_lines =
+      file_lines(&[("lib/a/x.rs", 10), ("lib/a/y.rs", 10), ("lib/b.rs", 20)]);
+    let report = build_report(
+      &file_lines,
+      [
+        ("lib/a/x.rs", 1, 4),
+        ("lib/a/y.rs", 5, 8),
+        // Overlapping copies are only counted once
+        ("lib/a/x.rs", 3, 4),
+        ("lib/b.rs", 19, 20),
+      ],
+    );
+
+    let summary = |items: &[DuplicatedLinesItem]| -> Vec<(String, usize)> {
+      items
+        .iter()
+        .map(|item| (item.path.clone(), item.lines.duplicated_lines))
+        .collect()
+    };
+    assert_eq!(
+      summary(&report.files),
+      [
+        ("lib/a/x.rs".to_string(), 4),
+        ("lib/a/y.rs".to_string(), 4),
+        ("lib/b.rs".to_string(), 2),
+      ]
+    );
+    assert_eq!(
+      summary(&report.directories),
+      [("lib/a".to_string(), 8), ("lib".to_string(), 10)]
+    );
+    assert_eq!(report.total, DuplicatedLines::new(40, 10));
+    assert_eq!(report.total.percentage, 25.0);
+  }
+
+  #[test]
+  fn test_format_report() {
+    let report = build_report(
+      &file_lines(&[("src/a.rs", 4), ("src/b.rs", 8)]),
+      [("src/a.rs", 1, 2), ("src/b.rs", 1, 2)],
+    );
+
+    assert_eq!(
+      format_report(&report),
+      "\
+Duplicated lines per file
+   Share  Duplicated  Lines  Path
+  50.00%           2      4  src/a.rs
+  25.00%           2      8  src/b.rs
+
+Duplicated lines per directory
+   Share  Duplicated  Lines  Path
+  33.33%           4     12  src
+
+Total: 4 of 12 lines duplicated (33.33%)
+"
+    );
+  }
+}
@@ -4,6 +4,7 @@ use memmap2::MmapOptions;
 use rayon::prelude::*;
 use std::error::Error;
 use std::fs::{self, File};
+use std::io::{self, Read};
 use std::path::{Path, PathBuf};
 
 /// Merge lines from multiple files that pass the given filter
@@ -96,6 +97,30 @@ pub fn find_all_files(dir: &Path) -> Result<Vec<PathBuf>, Box<dyn Error>> {
   Ok(files)
 }
 
+/// Resolve the given file and directory paths to a list of files.
+/// Directories are traversed recursively with `find_all_files`.
+pub fn resolve_paths(paths: &[String]) -> Result<Vec<PathBuf>, Box<dyn Error>> {
+  let mut all_files = Vec::new();
+
+  for path_str in paths {
+    let path = Path::new(path_str);
+
+    if path.is_file() {
+      all_files.push(path.to_path_buf());
+    } else if path.is_dir() {
+      all_files.extend(find_all_files(path)?);
+    } else {
+      return Err(format!("Path does not exist: {}", path.display()).into());
+    }
+  }
+
+  if all_files.is_empty() {
+    return Err("No valid files found in the specified paths".into());
+  }
+
+  Ok(all_files)
+}
+
 /// Load multiple files as FileEntry structs
 /// using memory mapping for improved performance
 pub fn load_files(
@@ -179,6 +204,32 @@ pub fn load_files(
   Ok(valid_entries)
 }
 
+/// Load the files at the given paths like `load_files(resolve_paths(…))`,
+/// but read the standard input for the path `-`.
+pub fn load_paths_or_stdin(
+  paths: &[String],
+) -> Result<Vec<FileEntry>, Box<dyn Error>> {
+  let (stdin_paths, file_paths): (Vec<String>, Vec<String>) =
+    paths.iter().cloned().partition(|path| path == "-");
+
+  let mut file_entries = if file_paths.is_empty() && !stdin_paths.is_empty() {
+    Vec::new()
+  } else {
+    load_files(resolve_paths(&file_paths)?)?
+  };
+
+  if !stdin_paths.is_empty() {
+    let mut content = String::new();
+    io::stdin().read_to_string(&mut content)?;
+    file_entries.push(FileEntry {
+      name: "-".to_string(),
+      content: MappedContent::String(content),
+    });
+  }
+
+  Ok(file_entries)
+}
+
 #[cfg(test)]
 mod tests {
   use super::*;
@@ -251,6 +302,31 @@ mod tests {
     Ok(())
   }
 
+  #[test]
+  fn test_resolve_paths() -> Result<(), Box<dyn Error>> {
+    let temp_dir = tempdir()?;
+    let temp_path = temp_dir.path();
+
+    let subdir = temp_path.join("subdir");
+    fs::create_dir(&subdir)?;
+    let file1 = temp_path.join("file1.txt");
+    let file2 = subdir.join("file2.txt");
+    File::create(&file1)?.write_all(b"Test content 1")?;
+    File::create(&file2)?.write_all(b"Test content 2")?;
+
+    let files = resolve_paths(&[
+      file1.to_string_lossy().into_owned(),
+      subdir.to_string_lossy().into_owned(),
+    ])?;
+    assert_eq!(files, vec![file1, file2]);
+
+    let missing = temp_path.join("missing.txt");
+    assert!(resolve_paths(&[missing.to_string_lossy().into_owned()]).is_err());
+    assert!(resolve_paths(&[]).is_err());
+
+    Ok(())
+  }
+
   #[test]
   fn test_load_files() -> Result<(), Box<dyn Error>> {
     // Create a temporary directory
@@ -1,8 +1,44 @@
-use pad::{Alignment, PadStr};
-use std::collections::HashMap;
+use crate::histogram::{render_histogram, HistogramRow};
+use crate::language::Language;
+use crate::readability::split_sentences;
+use crate::tokenizer::tokenize;
+use crate::types::{BarOptions, FileEntry, HistogramOptions};
+use crate::types::{SortOrder, TokenizerOptions};
+use rayon::prelude::*;
+use rust_stemmers::Stemmer;
+use std::cmp::Ordering;
+use std::collections::{HashMap, HashSet};
 use unicode_width::UnicodeWidthStr;
 
-const MAX_LINE_LENGTH: u16 = 80;
+/// Label of the histogram entry which sums up all cut off entries
+pub const OTHERS_LABEL: &str = "(others)";
+
+/// Options to configure how a frequency map is generated
+#[derive(Debug, Clone)]
+pub struct FrequencyOptions {
+  /// Number of consecutive words to count as one entry
+  /// (1 = single words, 2 = bigrams, 3 = trigrams, ...)
+  pub ngram: usize,
+  /// Words which are not counted.
+  /// N-grams are only left out if they consist solely of stop words.
+  pub stop_words: HashSet<String>,
+  /// Reduce words to their stem with the stemmer of the given language,
+  /// so that inflected forms like "runs" and "running" are counted as one
+  pub stem: Option<Language>,
+  /// Options for splitting the text into words
+  pub tokenizer: TokenizerOptions,
+}
+
+impl Default for FrequencyOptions {
+  fn default() -> Self {
+    FrequencyOptions {
+      ngram: 1,
+      stop_words: HashSet::new(),
+      stem: None,
+      tokenizer: TokenizerOptions::default(),
+    }
+  }
+}
 
 /// Generate a frequency map from a given text.
 ///
@@ -32,72 +68,303 @@ const MAX_LINE_LENGTH: u16 = 80;
 /// assert_eq!(freq_map, expected_map);
 /// ```
 pub fn generate_frequency_map(text: &str) -> HashMap<String, i32> {
-  let words = text
-    .split(|character| !char::is_alphabetic(character))
-    .filter(|word| word != &"");
+  generate_frequency_map_with_options(text, &FrequencyOptions::default())
+}
+
+/// Generate a frequency map from a given text with the given options.
+///
+/// N-grams are joined with a single space
+/// and don't span across sentence boundaries.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::frequency::{
+///   generate_frequency_map_with_options, FrequencyOptions,
+/// };
+///
+/// let freq_map = generate_frequency_map_with_options(
+///   "In order to win. We train in order to win.",
+///   &FrequencyOptions {
+///     ngram: 3,
+///     ..Default::default()
+///   },
+/// );
+///
+/// assert_eq!(freq_map["in order to"], 2);
+/// assert_eq!(freq_map["order to win"], 2);
+/// assert!(!freq_map.contains_key("to win we"));
+/// ```
+pub fn generate_frequency_map_with_options(
+  text: &str,
+  options: &FrequencyOptions,
+) -> HashMap<String, i32> {
   let mut frequency_map = HashMap::new();
+  visit_entries(text, options, |key, _surface_form| {
+    *frequency_map.entry(key).or_insert(0) += 1;
+  });
+  frequency_map
+}
 
-  for word in words {
-    let count = frequency_map.entry(word.to_lowercase()).or_insert(0);
-    *count += 1;
+/// Collect the surface forms (the words as they appear in the text)
+/// of each entry of the frequency map and how often each of them occurs.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::frequency::{generate_surface_forms, FrequencyOptions};
+/// use textalyzer::language::Language;
+///
+/// let forms = generate_surface_forms(
+///   "Run, he runs, they are running.",
+///   &FrequencyOptions {
+///     stem: Some(Language::English),
+///     ..Default::default()
+///   },
+/// );
+///
+/// assert_eq!(forms["run"].len(), 3);
+/// assert_eq!(forms["run"]["running"], 1);
+/// ```
+pub fn generate_surface_forms(
+  text: &str,
+  options: &FrequencyOptions,
+) -> HashMap<String, HashMap<String, i32>> {
+  let mut forms_map: HashMap<String, HashMap<String, i32>> = HashMap::new();
+  visit_entries(text, options, |key, surface_form| {
+    *forms_map
+      .entry(key)
+      .or_default()
+      .entry(surface_form.to_string())
+      .or_insert(0) += 1;
+  });
+  forms_map
+}
+
+/// Generate one frequency map for all files.
+/// The files are counted in parallel and their maps merged afterwards.
+pub fn generate_file_frequency_map(
+  files: &[FileEntry],
+  options: &FrequencyOptions,
+) -> HashMap<String, i32> {
+  files
+    .par_iter()
+    .filter_map(|file| {
+      let text = file.content.as_str()?;
+      Some(generate_frequency_map_with_options(text, options))
+    })
+    .reduce(HashMap::new, merge_counts)
+}
+
+/// Collect the surface forms of all files like `generate_surface_forms`.
+pub fn generate_file_surface_forms(
+  files: &[FileEntry],
+  options: &FrequencyOptions,
+) -> HashMap<String, HashMap<String, i32>> {
+  files
+    .par_iter()
+    .filter_map(|file| {
+      let text = file.content.as_str()?;
+      Some(generate_surface_forms(text, options))
+    })
+    .reduce(HashMap::new, |mut merged, forms_map| {
+      for (key, forms) in forms_map {
+        let merged_forms = merged.remove(&key).unwrap_or_default();
+        merged.insert(key, merge_counts(merged_forms, forms));
+      }
+      merged
+    })
+}
+
+/// Add the counts of the second map to the first one.
+fn merge_counts(
+  mut merged: HashMap<String, i32>,
+  counts: HashMap<String, i32>,
+) -> HashMap<String, i32> {
+  for (key, count) in counts {
+    *merged.entry(key).or_insert(0) += count;
   }
-  frequency_map
+  merged
 }
 
-/// Format a frequency map into a string.
-pub fn format_freq_map(freq_map: HashMap<String, i32>) -> String {
-  let mut freq_vec: Vec<_> = freq_map.iter().collect();
-  freq_vec.sort_by(|t1, t2| t2.1.cmp(t1.1));
-  let mut longest_word = "";
-  let mut highest_number = &0;
+/// Call `visit` with the key to count and the surface form
+/// of each word or n-gram in the text.
+fn visit_entries<F: FnMut(String, &str)>(
+  text: &str,
+  options: &FrequencyOptions,
+  mut visit: F,
+) {
+  let stemmer = options
+    .stem
+    .and_then(|language| language.stemming_algorithm())
+    .map(Stemmer::create);
+  let stem = |word: &String| match &stemmer {
+    Some(stemmer) => stemmer.stem(word).into_owned(),
+    None => word.clone(),
+  };
+  let is_stop_word = |word: &String| options.stop_words.contains(word);
 
-  for (word, count) in &freq_vec {
-    let word_length = UnicodeWidthStr::width(&word[..]);
+  if options.ngram <= 1 {
+    for word in tokenize(text, &options.tokenizer) {
+      if !is_stop_word(&word) {
+        visit(stem(&word), &word);
+      }
+    }
+    return;
+  }
 
-    if word_length > UnicodeWidthStr::width(longest_word) {
-      longest_word = word;
+  for sentence in split_sentences(text) {
+    let words = tokenize(sentence, &options.tokenizer);
+    let stems: Vec<String> = words.iter().map(stem).collect();
+    for (idx, ngram) in words.windows(options.ngram).enumerate() {
+      if !ngram.iter().all(is_stop_word) {
+        let key = stems[idx..idx + options.ngram].join(" ");
+        visit(key, &ngram.join(" "));
+      }
     }
-    if count > &highest_number {
-      highest_number = count;
+  }
+}
+
+/// Sort surface forms by frequency and then alphabetically.
+pub fn sort_surface_forms(forms: &HashMap<String, i32>) -> Vec<String> {
+  let mut forms_vec: Vec<_> = forms.iter().collect();
+  forms_vec.sort_by(|a, b| b.1.cmp(a.1).then(a.0.cmp(b.0)));
+  forms_vec
+    .into_iter()
+    .map(|(form, _)| form.clone())
+    .collect()
+}
+
+/// Entries of a frequency map which were selected for a histogram
+#[derive(Debug, Default, PartialEq)]
+pub struct FrequencySelection {
+  /// Selected entries in the order they should be shown
+  pub entries: Vec<(String, i32)>,
+  /// Number of cut off entries and the sum of their counts
+  pub others: Option<(usize, i32)>,
+}
+
+/// Sort the entries of a frequency map
+/// and cut off the ones which don't meet `--top` or `--min-count`.
+///
+/// Ties are always ordered alphabetically.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::frequency::{generate_frequency_map, select_frequencies};
+/// use textalyzer::types::HistogramOptions;
+///
+/// let selection = select_frequencies(
+///   generate_frequency_map("b a b c b a"),
+///   &HistogramOptions {
+///     top: Some(2),
+///     ..Default::default()
+///   },
+/// );
+///
+/// assert_eq!(
+///   selection.entries,
+///   vec![(String::from("b"), 3), (String::from("a"), 2)],
+/// );
+/// assert_eq!(selection.others, Some((1, 1)));
+/// ```
+pub fn select_frequencies(
+  freq_map: HashMap<String, i32>,
+  options: &HistogramOptions,
+) -> FrequencySelection {
+  let mut entries: Vec<(String, i32)> = freq_map.into_iter().collect();
+  entries.sort_by(|(word_a, count_a), (word_b, count_b)| {
+    let order = match options.sort {
+      SortOrder::Count => count_b.cmp(count_a),
+      SortOrder::Alpha => Ordering::Equal,
+      SortOrder::Length => UnicodeWidthStr::width(word_b.as_str())
+        .cmp(&UnicodeWidthStr::width(word_a.as_str())),
+    };
+    order.then_with(|| word_a.cmp(word_b))
+  });
+  if options.reverse {
+    entries.reverse();
+  }
+
+  let min_count = options.min_count.unwrap_or(0);
+  let (mut entries, mut cut): (Vec<_>, Vec<_>) = entries
+    .into_iter()
+    .partition(|(_, count)| *count >= min_count);
+  if let Some(top) = options.top {
+    if entries.len() > top {
+      cut.extend(entries.split_off(top));
     }
   }
 
-  let max_number_length = highest_number.to_string().len();
-  let max_word_length = UnicodeWidthStr::width(longest_word);
+  let others = if cut.is_empty() {
+    None
+  } else {
+    Some((cut.len(), cut.iter().map(|(_, count)| count).sum()))
+  };
 
-  let max_line_length = max_word_length + 2 + max_number_length + 2;
-  let remaining_space = MAX_LINE_LENGTH as usize - max_line_length;
+  FrequencySelection { entries, others }
+}
 
-  let mut result = String::new();
+/// Format a frequency map into a string.
+pub fn format_freq_map(freq_map: HashMap<String, i32>) -> String {
+  format_freq_map_with_forms(freq_map, &HashMap::new())
+}
 
-  for (word, count) in &freq_vec {
-    let bar_width =
-      (remaining_space as f32 / *highest_number as f32) * **count as f32;
+/// Format a frequency map into a string
+/// and list the surface forms of each entry after its bar.
+pub fn format_freq_map_with_forms(
+  freq_map: HashMap<String, i32>,
+  forms_map: &HashMap<String, HashMap<String, i32>>,
+) -> String {
+  let selection = select_frequencies(freq_map, &HistogramOptions::default());
+  format_freq_selection(&selection, forms_map, &BarOptions::default())
+}
 
-    result += &format!(
-      "{}  {}  {}\n",
-      word.pad_to_width_with_alignment(
-        max_word_length, // this comment fixes rustfmt
-        Alignment::Right
-      ),
-      count
-        .to_string()
-        .pad_to_width_with_alignment(max_number_length, Alignment::Right),
-      "▆".repeat(bar_width.round() as usize),
-    );
+/// Format the selected entries of a frequency map into a string,
+/// list the surface forms of each entry after its bar,
+/// and add a final line for the cut off entries.
+pub fn format_freq_selection(
+  selection: &FrequencySelection,
+  forms_map: &HashMap<String, HashMap<String, i32>>,
+  bars: &BarOptions,
+) -> String {
+  let mut rows: Vec<HistogramRow> = selection
+    .entries
+    .iter()
+    .map(|(word, count)| HistogramRow {
+      note: match forms_map.get(word) {
+        Some(forms) if forms.len() > 1 || !forms.contains_key(word) => {
+          format!("  ({})", sort_surface_forms(forms).join(", "))
+        }
+        _ => String::new(),
+      },
+      ..HistogramRow::new(word.as_str(), *count as usize)
+    })
+    .collect();
+
+  // The bar of the cut off entries is left out,
+  // as their sum would dwarf the bars of all other entries
+  if let Some((entries, count)) = selection.others {
+    rows.push(HistogramRow {
+      note: format!("({entries} entries)"),
+      summary: true,
+      ..HistogramRow::new(OTHERS_LABEL, count as usize)
+    });
   }
 
-  result
+  render_histogram(&rows, bars)
 }
 
 #[cfg(test)]
 mod tests {
   use super::*;
+  use crate::types::MappedContent;
 
   #[test]
   fn generate_frequency_map_from_text() {
     let text = "Hello World! A warm welcome to the world.";
-    let frequency_map = generate_frequency_map(&text);
+    let frequency_map = generate_frequency_map(text);
     let expected_map = [
       (String::from("a"), 1),
       (String::from("hello"), 1),
@@ -113,4 +380,204 @@ mod tests {
 
     assert_eq!(frequency_map, expected_map);
   }
+
+  #[test]
+  fn generate_bigram_frequency_map_from_text() {
+    let text = "Hello World! A warm welcome to the world.";
+    let options = FrequencyOptions {
+      ngram: 2,
+      ..Default::default()
+    };
+    let frequency_map = generate_frequency_map_with_options(text, &options);
+    let expected_map = [
+      (String::from("hello world"), 1),
+      (String::from("a warm"), 1),
+      (String::from("warm welcome"), 1),
+      (String::from("welcome to"), 1),
+      (String::from("to the"), 1),
+      (String::from("the world"), 1),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+
+    assert_eq!(frequency_map, expected_map);
+  }
+
+  #[test]
+  fn generate_ngram_frequency_map_from_short_text() {
+    let options = FrequencyOptions {
+      ngram: 3,
+      ..Default::default()
+    };
+    let frequency_map =
+      generate_frequency_map_with_options("Hello world", &options);
+
+    assert!(frequency_map.is_empty());
+  }
+
+  #[test]
+  fn generate_frequency_map_without_stop_words() {
+    let text = "The party is the party. In order to win.";
+    let stop_words: HashSet<String> = ["the", "is", "in", "to"]
+      .iter()
+      .map(|word| word.to_string())
+      .collect();
+
+    let words_options = FrequencyOptions {
+      stop_words: stop_words.clone(),
+      ..Default::default()
+    };
+    let words_map = generate_frequency_map_with_options(text, &words_options);
+    let expected_map = [
+      (String::from("party"), 2),
+      (String::from("order"), 1),
+      (String::from("win"), 1),
+    ]
+    .iter()
+    .cloned()
+    .collect();
+    assert_eq!(words_map, expected_map);
+
+    let bigram_options = FrequencyOptions {
+      ngram: 2,
+      stop_words,
+      ..Default::default()
+    };
+    let bigram_map = generate_frequency_map_with_options(text, &bigram_options);
+    assert_eq!(bigram_map["the party"], 2);
+    assert_eq!(bigram_map["in order"], 1);
+    assert!(!bigram_map.contains_key("is the"));
+    assert!(!bigram_map.contains_key("in to"));
+  }
+
+  #[test]
+  fn generate_stemmed_frequency_map() {
+    let english = FrequencyOptions {
+      stem: Some(Language::English),
+      ..Default::default()
+    };
+    let english_map =
+      generate_frequency_map_with_options("Run, runs, running!", &english);
+    assert_eq!(english_map, [(String::from("run"), 3)].into());
+
+    let german = FrequencyOptions {
+      stem: Some(Language::German),
+      ..Default::default()
+    };
+    let german_map =
+      generate_frequency_map_with_options("Die Birnen und die Birne", &german);
+    assert_eq!(german_map["birn"], 2);
+  }
+
+  #[test]
+  fn generate_frequency_map_from_files() {
+    let files = [
+      FileEntry {
+        name: "a.txt".to_string(),
+        content: MappedContent::String("Runs and walks".to_string()),
+      },
+      FileEntry {
+        name: "b.txt".to_string(),
+        content: MappedContent::String("Running!".to_string()),
+      },
+    ];
+    let options = FrequencyOptions {
+      stem: Some(Language::English),
+      ..Default::default()
+    };
+
+    let freq_map = generate_file_frequency_map(&files, &options);
+    assert_eq!(freq_map["run"], 2);
+    assert_eq!(freq_map["walk"], 1);
+
+    let forms_map = generate_file_surface_forms(&files, &options);
+    assert_eq!(sort_surface_forms(&forms_map["run"]), ["running", "runs"]);
+  }
+
+  #[test]
+  fn select_frequencies_with_options() {
+    let freq_map: HashMap<String, i32> =
+      [("bb", 1), ("a", 3), ("ccc", 2), ("d", 2)]
+        .into_iter()
+        .map(|(word, count)| (word.to_string(), count))
+        .collect();
+    let words = |options: HistogramOptions| {
+      let selection = select_frequencies(freq_map.clone(), &options);
+      let words: Vec<String> = selection
+        .entries
+        .into_iter()
+        .map(|(word, _)| word)
+        .collect();
+      (words, selection.others)
+    };
+
+    assert_eq!(
+      words(HistogramOptions::default()),
+      (
+        vec!["a".into(), "ccc".into(), "d".into(), "bb".into()],
+        None
+      )
+    );
+    assert_eq!(
+      words(HistogramOptions {
+        sort: SortOrder::Alpha,
+        reverse: true,
+        ..Default::default()
+      }),
+      (
+        vec!["d".into(), "ccc".into(), "bb".into(), "a".into()],
+        None
+      )
+    );
+    assert_eq!(
+      words(HistogramOptions {
+        sort: SortOrder::Length,
+        top: Some(1),
+        ..Default::default()
+      }),
+      (vec!["ccc".into()], Some((3, 6)))
+    );
+    assert_eq!(
+      words(HistogramOptions {
+        min_count: Some(2),
+        top: Some(2),
+        ..Default::default()
+      }),
+      (vec!["a".into(), "ccc".into()], Some((2, 3)))
+    );
+  }
+
+  #[test]
+  fn format_freq_selection_with_others() {
+    let selection = FrequencySelection {
+      entries: vec![("war".to_string(), 4), ("peace".to_string(), 2)],
+      others: Some((12, 15)),
+    };
+    let formatted = format_freq_selection(
+      &selection,
+      &HashMap::new(),
+      &BarOptions::default(),
+    );
+    let lines: Vec<&str> = formatted.lines().collect();
+
+    assert!(lines[0].starts_with("     war   4  ▆"));
+    assert!(lines[1].starts_with("   peace   2  ▆"));
+    assert_eq!(lines[2], "(others)  15  (12 entries)");
+  }
+
+  #[test]
+  fn format_freq_map_lists_surface_forms() {
+    let text = "Runs, running, runs. Walk.";
+    let options = FrequencyOptions {
+      stem: Some(Language::English),
+      ..Default::default()
+    };
+    let freq_map = generate_frequency_map_with_options(text, &options);
+    let forms_map = generate_surface_forms(text, &options);
+    let formatted = format_freq_map_with_forms(freq_map, &forms_map);
+
+    assert!(formatted.contains("(runs, running)\n"));
+    assert!(formatted.trim_end().ends_with('▆'));
+  }
 }
@@ -0,0 +1,194 @@
+use crate::types::BarOptions;
+use pad::{Alignment, PadStr};
+use terminal_size::{terminal_size, Width};
+use unicode_width::UnicodeWidthStr;
+
+/// Width of a histogram if neither `--width` nor a terminal is given
+pub const DEFAULT_WIDTH: usize = 80;
+
+/// Bars never get shorter than this, even if the labels are very long
+const MIN_BAR_WIDTH: usize = 10;
+
+const BAR_CHAR: &str = "▆";
+const FULL_BLOCK: char = '█';
+/// Left blocks from one eighth to seven eighths of a full block
+const FRACTIONAL_BLOCKS: [char; 7] = ['▏', '▎', '▍', '▌', '▋', '▊', '▉'];
+
+/// A row of a histogram
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistogramRow {
+  pub label: String,
+  pub count: usize,
+  /// Text which is appended after the bar
+  pub note: String,
+  /// Summary rows like "(others)" get no bar
+  /// and don't affect the scale of the other bars
+  pub summary: bool,
+}
+
+impl HistogramRow {
+  pub fn new(label: impl Into<String>, count: usize) -> HistogramRow {
+    HistogramRow {
+      label: label.into(),
+      count,
+      note: String::new(),
+      summary: false,
+    }
+  }
+}
+
+/// Width of the terminal or `DEFAULT_WIDTH` if it can't be determined.
+pub fn terminal_width() -> usize {
+  terminal_size()
+    .map(|(Width(width), _)| width as usize)
+    .unwrap_or(DEFAULT_WIDTH)
+}
+
+/// Use the width of the terminal unless a width was given explicitly.
+pub fn fit_to_terminal(options: BarOptions) -> BarOptions {
+  BarOptions {
+    width: Some(options.width.unwrap_or_else(terminal_width)),
+    ..options
+  }
+}
+
+/// Draw a bar of the given width in columns.
+fn draw_bar(width: f64, fractional: bool) -> String {
+  if !fractional {
+    return BAR_CHAR.repeat(width.round() as usize);
+  }
+
+  let eighths = (width * 8.0).round() as usize;
+  let mut bar: String = std::iter::repeat_n(FULL_BLOCK, eighths / 8).collect();
+  let remainder = eighths % 8;
+  if remainder > 0 {
+    bar.push(FRACTIONAL_BLOCKS[remainder - 1]);
+  }
+  bar
+}
+
+/// Render rows as a histogram with right aligned labels and counts
+/// followed by bars which fit into the width of the options.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::histogram::{render_histogram, HistogramRow};
+/// use textalyzer::types::BarOptions;
+///
+/// let rows = vec![HistogramRow::new("war", 4), HistogramRow::new("peace", 1)];
+/// let options = BarOptions {
+///   width: Some(20),
+///   fractional_bars: true,
+/// };
+///
+/// assert_eq!(
+///   render_histogram(&rows, &options),
+///   "  war  4  ██████████\npeace  1  ██▌\n",
+/// );
+/// ```
+pub fn render_histogram(rows: &[HistogramRow], options: &BarOptions) -> String {
+  let label_width = rows
+    .iter()
+    .map(|row| UnicodeWidthStr::width(row.label.as_str()))
+    .max()
+    .unwrap_or(0);
+  let count_width = rows
+    .iter()
+    .map(|row| row.count.to_string().len())
+    .max()
+    .unwrap_or(0);
+  let max_count = rows
+    .iter()
+    .filter(|row| !row.summary)
+    .map(|row| row.count)
+    .max()
+    .unwrap_or(0);
+
+  let width = options.width.unwrap_or(DEFAULT_WIDTH);
+  let bar_space = width
+    .saturating_sub(label_width + 2 + count_width + 2)
+    .max(MIN_BAR_WIDTH);
+
+  let mut result = String::new();
+
+  for row in rows {
+    let bar = if row.summary || max_count == 0 {
+      String::new()
+    } else {
+      let bar_width = bar_space as f64 / max_count as f64 * row.count as f64;
+      draw_bar(bar_width, options.fractional_bars)
+    };
+
+    result += &format!(
+      "{}  {}  {}{}\n",
+      row
+        .label
+        .pad_to_width_with_alignment(label_width, Alignment::Right),
+      row
+        .count
+        .to_string()
+        .pad_to_width_with_alignment(count_width, Alignment::Right),
+      bar,
+      row.note,
+    );
+  }
+
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_draw_bar() {
+    assert_eq!(draw_bar(2.4, false), "▆▆");
+    assert_eq!(draw_bar(2.4, true), "██▍");
+    assert_eq!(draw_bar(0.0, true), "");
+  }
+
+  #[test]
+  fn test_render_histogram_fits_width() {
+    let rows = vec![HistogramRow::new("a", 10), HistogramRow::new("b", 5)];
+    let options = BarOptions {
+      width: Some(30),
+      ..Default::default()
+    };
+    let rendered = render_histogram(&rows, &options);
+    let lines: Vec<&str> = rendered.lines().collect();
+
+    assert_eq!(UnicodeWidthStr::width(lines[0]), 30);
+    assert_eq!(lines[1], format!("b   5  {}", "▆".repeat(12)));
+  }
+
+  #[test]
+  fn test_render_histogram_with_long_label() {
+    let long_label = "x".repeat(100);
+    let rows = vec![HistogramRow::new(long_label, 3)];
+    let rendered = render_histogram(&rows, &BarOptions::default());
+
+    assert!(rendered.ends_with(&format!("  3  {}\n", "▆".repeat(10))));
+  }
+
+  #[test]
+  fn test_render_histogram_with_summary_row() {
+    let rows = vec![
+      HistogramRow::new("a", 2),
+      HistogramRow {
+        note: "(3 entries)".to_string(),
+        summary: true,
+        ..HistogramRow::new("(others)", 9)
+      },
+    ];
+    let options = BarOptions {
+      width: Some(20),
+      ..Default::default()
+    };
+
+    assert_eq!(
+      render_histogram(&rows, &options),
+      "       a  2  ▆▆▆▆▆▆▆▆▆▆\n(others)  9  (3 entries)\n"
+    );
+  }
+}
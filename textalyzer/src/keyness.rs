@@ -0,0 +1,283 @@
+use crate::significance::{chi_squared, log_likelihood};
+use crate::types::{
+  FrequencyComparison, FrequencyItem, KeynessItem, KeynessMeasure,
+};
+use pad::{Alignment, PadStr};
+use std::collections::HashMap;
+
+fn sort_by_score(items: &mut [KeynessItem]) {
+  items.sort_by(|a, b| b.score.total_cmp(&a.score).then(a.word.cmp(&b.word)));
+}
+
+fn sort_by_count(items: &mut [FrequencyItem]) {
+  items.sort_by(|a, b| b.count.cmp(&a.count).then(a.word.cmp(&b.word)));
+}
+
+/// Compare the frequency maps of two texts and keep the `top` words
+/// of each list.
+///
+/// Words which occur in both texts are scored by how much more frequent
+/// they are in one text than in the other relative to the size of the
+/// texts. Words which occur in only one of the texts are listed
+/// separately by their count.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::frequency::generate_frequency_map;
+/// use textalyzer::keyness::compare_frequencies;
+/// use textalyzer::types::KeynessMeasure;
+///
+/// let comparison = compare_frequencies(
+///   ("a.txt", &generate_frequency_map("war war war and peace")),
+///   ("b.txt", &generate_frequency_map("peace peace and war love")),
+///   KeynessMeasure::LogLikelihood,
+///   10,
+/// );
+///
+/// assert_eq!(comparison.overrepresented_in_a[0].word, "war");
+/// assert_eq!(comparison.overrepresented_in_b[0].word, "peace");
+/// assert_eq!(comparison.only_in_b[0].word, "love");
+/// assert!(comparison.only_in_a.is_empty());
+/// ```
+pub fn compare_frequencies(
+  (name_a, freq_map_a): (&str, &HashMap<String, i32>),
+  (name_b, freq_map_b): (&str, &HashMap<String, i32>),
+  measure: KeynessMeasure,
+  top: usize,
+) -> FrequencyComparison {
+  let total_a = freq_map_a.values().sum::<i32>() as f64;
+  let total_b = freq_map_b.values().sum::<i32>() as f64;
+
+  let mut overrepresented_in_a = Vec::new();
+  let mut overrepresented_in_b = Vec::new();
+  let mut only_in_a = Vec::new();
+
+  for (word, &count_a) in freq_map_a {
+    let Some(&count_b) = freq_map_b.get(word) else {
+      only_in_a.push(FrequencyItem {
+        word: word.clone(),
+        count: count_a,
+        forms: None,
+        others: None,
+      });
+      continue;
+    };
+
+    let (a, b) = (count_a as f64, count_b as f64);
+    let score = match measure {
+      KeynessMeasure::LogLikelihood => {
+        log_likelihood(a, b, total_a - a, total_b - b)
+      }
+      KeynessMeasure::ChiSquared => chi_squared(a, b, total_a - a, total_b - b),
+    };
+    let item = KeynessItem {
+      word: word.clone(),
+      count_a,
+      count_b,
+      score,
+    };
+
+    let relative_a = a / total_a;
+    let relative_b = b / total_b;
+    if relative_a > relative_b {
+      overrepresented_in_a.push(item);
+    } else if relative_b > relative_a {
+      overrepresented_in_b.push(item);
+    }
+  }
+
+  let mut only_in_b: Vec<FrequencyItem> = freq_map_b
+    .iter()
+    .filter(|(word, _)| !freq_map_a.contains_key(*word))
+    .map(|(word, &count)| FrequencyItem {
+      word: word.clone(),
+      count,
+      forms: None,
+      others: None,
+    })
+    .collect();
+
+  sort_by_score(&mut overrepresented_in_a);
+  sort_by_score(&mut overrepresented_in_b);
+  sort_by_count(&mut only_in_a);
+  sort_by_count(&mut only_in_b);
+  overrepresented_in_a.truncate(top);
+  overrepresented_in_b.truncate(top);
+  only_in_a.truncate(top);
+  only_in_b.truncate(top);
+
+  FrequencyComparison {
+    a: name_a.to_string(),
+    b: name_b.to_string(),
+    overrepresented_in_a,
+    overrepresented_in_b,
+    only_in_a,
+    only_in_b,
+  }
+}
+
+/// Format a table with one row per word and right-aligned number columns.
+fn format_table(title: &str, rows: &[(String, Vec<String>)]) -> String {
+  let mut result = format!("{title}\n");
+  if rows.is_empty() {
+    result += "  -\n";
+    return result;
+  }
+
+  let word_width = rows
+    .iter()
+    .map(|(word, _)| word.chars().count())
+    .max()
+    .unwrap_or(0);
+  let column_count = rows[0].1.len();
+  let column_widths: Vec<usize> = (0..column_count)
+    .map(|column| {
+      rows
+        .iter()
+        .map(|(_, values)| values[column].len())
+        .max()
+        .unwrap_or(0)
+    })
+    .collect();
+
+  for (word, values) in rows {
+    result += "  ";
+    result += &word.pad_to_width_with_alignment(word_width, Alignment::Left);
+    for (value, width) in values.iter().zip(&column_widths) {
+      result += "  ";
+      result += &value.pad_to_width_with_alignment(*width, Alignment::Right);
+    }
+    result += "\n";
+  }
+
+  result
+}
+
+/// Format a frequency comparison as four tables.
+/// The keyness tables show the counts in the first and the second text,
+/// followed by the score.
+pub fn format_comparison(comparison: &FrequencyComparison) -> String {
+  let keyness_rows = |items: &[KeynessItem]| -> Vec<(String, Vec<String>)> {
+    items
+      .iter()
+      .map(|item| {
+        (
+          item.word.clone(),
+          vec![
+            item.count_a.to_string(),
+            item.count_b.to_string(),
+            format!("{:.2}", item.score),
+          ],
+        )
+      })
+      .collect()
+  };
+  let count_rows = |items: &[FrequencyItem]| -> Vec<(String, Vec<String>)> {
+    items
+      .iter()
+      .map(|item| (item.word.clone(), vec![item.count.to_string()]))
+      .collect()
+  };
+
+  [
+    format_table(
+      &format!("Overrepresented in {}", comparison.a),
+      &keyness_rows(&comparison.overrepresented_in_a),
+    ),
+    format_table(
+      &format!("Overrepresented in {}", comparison.b),
+      &keyness_rows(&comparison.overrepresented_in_b),
+    ),
+    format_table(
+      &format!("Only in {}", comparison.a),
+      &count_rows(&comparison.only_in_a),
+    ),
+    format_table(
+      &format!("Only in {}", comparison.b),
+      &count_rows(&comparison.only_in_b),
+    ),
+  ]
+  .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn freq_map(pairs: &[(&str, i32)]) -> HashMap<String, i32> {
+    pairs
+      .iter()
+      .map(|(word, count)| (word.to_string(), *count))
+      .collect()
+  }
+
+  #[test]
+  fn test_compare_frequencies() {
+    let a = freq_map(&[("the", 50), ("war", 20), ("peace", 5), ("tank", 3)]);
+    let b = freq_map(&[("the", 100), ("war", 4), ("peace", 40), ("dove", 2)]);
+
+    let comparison =
+      compare_frequencies(("a", &a), ("b", &b), KeynessMeasure::ChiSquared, 10);
+    let words = |items: &[KeynessItem]| -> Vec<String> {
+      items.iter().map(|item| item.word.clone()).collect()
+    };
+
+    // "the" makes up a larger share of the words of b
+    assert_eq!(words(&comparison.overrepresented_in_a), ["war"]);
+    assert_eq!(words(&comparison.overrepresented_in_b), ["peace", "the"]);
+    assert_eq!(comparison.only_in_a[0].word, "tank");
+    assert_eq!(comparison.only_in_b[0].word, "dove");
+    let war = &comparison.overrepresented_in_a[0];
+    assert_eq!((war.count_a, war.count_b), (20, 4));
+    assert_eq!(war.score, chi_squared(20.0, 4.0, 58.0, 142.0));
+  }
+
+  #[test]
+  fn test_compare_frequencies_top() {
+    let a = freq_map(&[("x", 1), ("y", 2), ("z", 3)]);
+    let comparison = compare_frequencies(
+      ("a", &a),
+      ("b", &HashMap::new()),
+      KeynessMeasure::LogLikelihood,
+      2,
+    );
+
+    let words: Vec<&str> = comparison
+      .only_in_a
+      .iter()
+      .map(|item| item.word.as_str())
+      .collect();
+    assert_eq!(words, ["z", "y"]);
+  }
+
+  #[test]
+  fn test_format_comparison() {
+    let comparison = FrequencyComparison {
+      a: "a.txt".to_string(),
+      b: "b.txt".to_string(),
+      overrepresented_in_a: vec![KeynessItem {
+        word: "war".to_string(),
+        count_a: 20,
+        count_b: 4,
+        score: 12.5,
+      }],
+      overrepresented_in_b: vec![],
+      only_in_a: vec![],
+      only_in_b: vec![FrequencyItem {
+        word: "dove".to_string(),
+        count: 2,
+        forms: None,
+        others: None,
+      }],
+    };
+
+    assert_eq!(
+      format_comparison(&comparison),
+      "Overrepresented in a.txt\n  war  20  4  12.50\n\n\
+       Overrepresented in b.txt\n  -\n\n\
+       Only in a.txt\n  -\n\n\
+       Only in b.txt\n  dove  2\n"
+    );
+  }
+}
@@ -0,0 +1,187 @@
+use crate::frequency::{generate_frequency_map_with_options, FrequencyOptions};
+use crate::types::{DocumentKeywords, FileEntry, KeywordScore};
+use pad::{Alignment, PadStr};
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Calculate the TF-IDF score of each term of each document
+/// and keep the `top` terms with the highest scores per document.
+///
+/// The term frequency is the count of a term divided by the number of
+/// terms in the document, the inverse document frequency is
+/// `ln(documents / documents containing the term)`.
+/// Terms which occur in every document get a score of 0 and are left out.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::frequency::FrequencyOptions;
+/// use textalyzer::keywords::extract_keywords;
+///
+/// let documents = vec![
+///   ("a.txt".to_string(), "The cat sat on the mat."),
+///   ("b.txt".to_string(), "The dog sat on the log."),
+/// ];
+/// let keywords = extract_keywords(&documents, &FrequencyOptions::default(), 1);
+///
+/// assert_eq!(keywords[0].keywords[0].term, "cat");
+/// assert_eq!(keywords[1].keywords[0].term, "dog");
+/// ```
+pub fn extract_keywords(
+  documents: &[(String, &str)],
+  options: &FrequencyOptions,
+  top: usize,
+) -> Vec<DocumentKeywords> {
+  let freq_maps: Vec<HashMap<String, i32>> = documents
+    .par_iter()
+    .map(|(_, text)| generate_frequency_map_with_options(text, options))
+    .collect();
+
+  let mut document_frequency: HashMap<&str, usize> = HashMap::new();
+  for freq_map in &freq_maps {
+    for term in freq_map.keys() {
+      *document_frequency.entry(term).or_insert(0) += 1;
+    }
+  }
+  let document_count = documents.len() as f64;
+
+  documents
+    .iter()
+    .zip(&freq_maps)
+    .map(|((path, _), freq_map)| {
+      let term_count: i32 = freq_map.values().sum();
+      let mut keywords: Vec<KeywordScore> = freq_map
+        .iter()
+        .map(|(term, &count)| {
+          let idf =
+            (document_count / document_frequency[term.as_str()] as f64).ln();
+          KeywordScore {
+            term: term.clone(),
+            count,
+            tf_idf: count as f64 / term_count as f64 * idf,
+          }
+        })
+        .filter(|keyword| keyword.tf_idf > 0.0)
+        .collect();
+      keywords.sort_by(|a, b| {
+        b.tf_idf.total_cmp(&a.tf_idf).then(a.term.cmp(&b.term))
+      });
+      keywords.truncate(top);
+
+      DocumentKeywords {
+        path: path.clone(),
+        keywords,
+      }
+    })
+    .collect()
+}
+
+/// Extract the keywords of each file like `extract_keywords`.
+pub fn extract_file_keywords(
+  files: &[FileEntry],
+  options: &FrequencyOptions,
+  top: usize,
+) -> Vec<DocumentKeywords> {
+  let documents: Vec<(String, &str)> = files
+    .iter()
+    .filter_map(|file| Some((file.name.clone(), file.content.as_str()?)))
+    .collect();
+  extract_keywords(&documents, options, top)
+}
+
+/// Format the keywords of a document as a table.
+pub fn format_keywords(document: &DocumentKeywords) -> String {
+  let mut result = format!("{}\n", document.path);
+  if document.keywords.is_empty() {
+    result += "  No distinctive terms\n";
+    return result;
+  }
+
+  let term_width = document
+    .keywords
+    .iter()
+    .map(|keyword| keyword.term.chars().count())
+    .max()
+    .unwrap_or(0);
+  let count_width = document
+    .keywords
+    .iter()
+    .map(|keyword| keyword.count.to_string().len())
+    .max()
+    .unwrap_or(0);
+
+  for keyword in &document.keywords {
+    result += &format!(
+      "  {}  {}  {:.4}\n",
+      keyword
+        .term
+        .pad_to_width_with_alignment(term_width, Alignment::Left),
+      keyword
+        .count
+        .to_string()
+        .pad_to_width_with_alignment(count_width, Alignment::Right),
+      keyword.tf_idf,
+    );
+  }
+
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_extract_keywords() {
+    let documents = vec![
+      ("a".to_string(), "war war peace the the the"),
+      ("b".to_string(), "peace love the"),
+      ("c".to_string(), "love the"),
+    ];
+    let keywords =
+      extract_keywords(&documents, &FrequencyOptions::default(), 5);
+
+    let terms: Vec<&str> = keywords[0]
+      .keywords
+      .iter()
+      .map(|keyword| keyword.term.as_str())
+      .collect();
+    // "the" occurs in every document
+    assert_eq!(terms, vec!["war", "peace"]);
+    assert_eq!(keywords[0].keywords[0].count, 2);
+    assert_eq!(keywords[0].keywords[0].tf_idf, 2.0 / 6.0 * 3f64.ln());
+  }
+
+  #[test]
+  fn test_extract_keywords_of_single_document() {
+    let documents = vec![("a".to_string(), "war and peace")];
+    let keywords =
+      extract_keywords(&documents, &FrequencyOptions::default(), 5);
+
+    assert!(keywords[0].keywords.is_empty());
+  }
+
+  #[test]
+  fn test_format_keywords() {
+    let document = DocumentKeywords {
+      path: "a.txt".to_string(),
+      keywords: vec![
+        KeywordScore {
+          term: "war".to_string(),
+          count: 12,
+          tf_idf: 0.5,
+        },
+        KeywordScore {
+          term: "peace".to_string(),
+          count: 3,
+          tf_idf: 0.25,
+        },
+      ],
+    };
+
+    assert_eq!(
+      format_keywords(&document),
+      "a.txt\n  war    12  0.5000\n  peace   3  0.2500\n"
+    );
+  }
+}
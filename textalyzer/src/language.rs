@@ -0,0 +1,248 @@
+use rust_stemmers::Algorithm;
+use serde::Serialize;
+use std::collections::HashSet;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Natural languages with bundled resources in the `languages` directory
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Language {
+  English,
+  German,
+  French,
+  Spanish,
+  Chinese,
+  Japanese,
+  Thai,
+}
+
+impl Language {
+  /// All languages with bundled resources
+  pub const ALL: [Language; 7] = [
+    Language::English,
+    Language::German,
+    Language::French,
+    Language::Spanish,
+    Language::Chinese,
+    Language::Japanese,
+    Language::Thai,
+  ];
+
+  /// Name of the language, which is also its directory in `languages`
+  pub fn name(&self) -> &'static str {
+    match self {
+      Language::English => "english",
+      Language::German => "german",
+      Language::French => "french",
+      Language::Spanish => "spanish",
+      Language::Chinese => "chinese",
+      Language::Japanese => "japanese",
+      Language::Thai => "thai",
+    }
+  }
+
+  /// ISO 639-1 code of the language
+  pub fn code(&self) -> &'static str {
+    match self {
+      Language::English => "en",
+      Language::German => "de",
+      Language::French => "fr",
+      Language::Spanish => "es",
+      Language::Chinese => "zh",
+      Language::Japanese => "ja",
+      Language::Thai => "th",
+    }
+  }
+
+  /// Snowball stemming algorithm of the language, if it has one
+  pub fn stemming_algorithm(&self) -> Option<Algorithm> {
+    match self {
+      Language::English => Some(Algorithm::English),
+      Language::German => Some(Algorithm::German),
+      Language::French => Some(Algorithm::French),
+      Language::Spanish => Some(Algorithm::Spanish),
+      Language::Chinese | Language::Japanese | Language::Thai => None,
+    }
+  }
+
+  /// Bundled list of stop words with one word per line
+  pub fn stop_words(&self) -> &'static str {
+    match self {
+      Language::English => {
+        include_str!("../languages/english/stop-words.txt")
+      }
+      Language::German => include_str!("../languages/german/stop-words.txt"),
+      Language::French => include_str!("../languages/french/stop-words.txt"),
+      Language::Spanish => {
+        include_str!("../languages/spanish/stop-words.txt")
+      }
+      Language::Chinese => {
+        include_str!("../languages/chinese/stop-words.txt")
+      }
+      Language::Japanese => {
+        include_str!("../languages/japanese/stop-words.txt")
+      }
+      Language::Thai => include_str!("../languages/thai/stop-words.txt"),
+    }
+  }
+
+  /// Bundled table of relative letter frequencies in percent
+  /// with a letter and its frequency per line, separated by a tab
+  pub fn character_frequencies(&self) -> Option<&'static str> {
+    match self {
+      Language::English => Some(include_str!(
+        "../languages/english/relative-character-frequency.tsv"
+      )),
+      Language::German => Some(include_str!(
+        "../languages/german/relative-character-frequency.tsv"
+      )),
+      Language::French => Some(include_str!(
+        "../languages/french/relative-character-frequency.tsv"
+      )),
+      Language::Spanish => Some(include_str!(
+        "../languages/spanish/relative-character-frequency.tsv"
+      )),
+      _ => None,
+    }
+  }
+
+  /// Bundled profile of the most frequent character trigrams,
+  /// ordered by frequency with one trigram per line
+  pub fn trigram_profile(&self) -> Option<&'static str> {
+    match self {
+      Language::English => {
+        Some(include_str!("../languages/english/trigrams.txt"))
+      }
+      Language::German => {
+        Some(include_str!("../languages/german/trigrams.txt"))
+      }
+      Language::French => {
+        Some(include_str!("../languages/french/trigrams.txt"))
+      }
+      Language::Spanish => {
+        Some(include_str!("../languages/spanish/trigrams.txt"))
+      }
+      _ => None,
+    }
+  }
+
+  /// Bundled dictionary with one word per line
+  /// for languages which are written without spaces between words
+  pub fn dictionary(&self) -> Option<&'static str> {
+    match self {
+      Language::Chinese => Some(include_str!("../languages/chinese/words.txt")),
+      Language::Japanese => {
+        Some(include_str!("../languages/japanese/words.txt"))
+      }
+      Language::Thai => Some(include_str!("../languages/thai/words.txt")),
+      _ => None,
+    }
+  }
+}
+
+impl fmt::Display for Language {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "{}", self.name())
+  }
+}
+
+impl FromStr for Language {
+  type Err = String;
+
+  /// Parse a language from its name or its ISO 639-1 code
+  fn from_str(value: &str) -> Result<Self, Self::Err> {
+    let value = value.to_lowercase();
+    Language::ALL
+      .into_iter()
+      .find(|lang| lang.name() == value || lang.code() == value)
+      .ok_or_else(|| format!("Unsupported language: {value}"))
+  }
+}
+
+/// Parse a word list with one word per line.
+/// Empty lines and lines starting with `#` are ignored.
+pub fn parse_word_list(content: &str) -> HashSet<String> {
+  content
+    .lines()
+    .map(str::trim)
+    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+    .map(str::to_lowercase)
+    .collect()
+}
+
+/// Load stop words from a bundled language list
+/// or, if no such language exists, from a custom word list file.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::language::load_stop_words;
+///
+/// let stop_words = load_stop_words("english").unwrap();
+///
+/// assert!(stop_words.contains("the"));
+/// assert!(!stop_words.contains("party"));
+/// ```
+pub fn load_stop_words(
+  lang_or_file: &str,
+) -> Result<HashSet<String>, Box<dyn Error>> {
+  if let Ok(language) = Language::from_str(lang_or_file) {
+    return Ok(parse_word_list(language.stop_words()));
+  }
+
+  let path = Path::new(lang_or_file);
+  if path.is_file() {
+    Ok(parse_word_list(&fs::read_to_string(path)?))
+  } else {
+    Err(
+      format!(
+        "\"{lang_or_file}\" is neither a supported language ({}) \
+        nor a stop word file",
+        Language::ALL.map(|lang| lang.name()).join(", ")
+      )
+      .into(),
+    )
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use std::io::Write;
+  use tempfile::NamedTempFile;
+
+  #[test]
+  fn test_language_from_str() {
+    assert_eq!("de".parse::<Language>(), Ok(Language::German));
+    assert_eq!("English".parse::<Language>(), Ok(Language::English));
+    assert!("klingon".parse::<Language>().is_err());
+  }
+
+  #[test]
+  fn test_bundled_stop_words() {
+    for language in Language::ALL {
+      let stop_words = parse_word_list(language.stop_words());
+      assert!(stop_words.len() > 50, "Too few stop words for {language}");
+    }
+  }
+
+  #[test]
+  fn test_load_stop_words_from_file() -> Result<(), Box<dyn Error>> {
+    let mut file = NamedTempFile::new()?;
+    writeln!(file, "# Custom stop words\nFoo\n\n  bar  ")?;
+
+    let stop_words = load_stop_words(&file.path().to_string_lossy())?;
+
+    assert_eq!(
+      stop_words,
+      ["foo", "bar"].iter().map(|s| s.to_string()).collect()
+    );
+    assert!(load_stop_words("/does/not/exist.txt").is_err());
+
+    Ok(())
+  }
+}
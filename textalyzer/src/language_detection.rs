@@ -0,0 +1,321 @@
+use crate::language::Language;
+use crate::segmenter::{detect_cjk_language, script_of, Script};
+use crate::types::{LanguageGuess, LanguageScore};
+use pad::{Alignment, PadStr};
+use std::collections::HashMap;
+
+/// Number of trigrams in a language profile
+const PROFILE_SIZE: usize = 300;
+
+/// Count the character trigrams of all words in a text.
+/// Words are lowercased and padded with "_" to capture their start and end.
+fn count_trigrams(text: &str) -> HashMap<String, usize> {
+  let mut counts = HashMap::new();
+
+  for word in text
+    .split(|character: char| !character.is_alphabetic())
+    .filter(|word| !word.is_empty())
+  {
+    let padded: Vec<char> = std::iter::once('_')
+      .chain(word.to_lowercase().chars())
+      .chain(std::iter::once('_'))
+      .collect();
+
+    for trigram in padded.windows(3) {
+      *counts.entry(trigram.iter().collect()).or_insert(0) += 1;
+    }
+  }
+
+  counts
+}
+
+/// Build a profile of the most frequent character trigrams of a text,
+/// ordered by frequency and then alphabetically.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::language_detection::trigram_profile;
+///
+/// let profile = trigram_profile("the theme");
+///
+/// assert_eq!(profile[..3], ["_th", "the", "eme"]);
+/// ```
+pub fn trigram_profile(text: &str) -> Vec<String> {
+  let mut trigrams: Vec<(String, usize)> =
+    count_trigrams(text).into_iter().collect();
+  trigrams.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+  trigrams
+    .into_iter()
+    .take(PROFILE_SIZE)
+    .map(|(trigram, _)| trigram)
+    .collect()
+}
+
+/// Parse a bundled profile with one trigram per line into trigram ranks.
+fn parse_profile(content: &str) -> HashMap<&str, usize> {
+  content
+    .lines()
+    .filter(|line| !line.is_empty())
+    .enumerate()
+    .map(|(rank, trigram)| (trigram, rank))
+    .collect()
+}
+
+/// Cavnar-Trenkle "out-of-place" distance between a text profile and
+/// a language profile, normalized to the range 0 (equal) to 1 (disjoint).
+fn profile_distance(
+  text_profile: &[String],
+  language_profile: &HashMap<&str, usize>,
+) -> f64 {
+  if text_profile.is_empty() {
+    return 1.0;
+  }
+
+  let distance: usize = text_profile
+    .iter()
+    .enumerate()
+    .map(
+      |(rank, trigram)| match language_profile.get(trigram.as_str()) {
+        Some(language_rank) => rank.abs_diff(*language_rank).min(PROFILE_SIZE),
+        None => PROFILE_SIZE,
+      },
+    )
+    .sum();
+
+  distance as f64 / (text_profile.len() * PROFILE_SIZE) as f64
+}
+
+/// Guess the language of a text written in a script without spaces
+/// if the majority of its letters belongs to such a script.
+fn detect_language_by_script(text: &str) -> Option<LanguageGuess> {
+  let mut letters = 0;
+  let mut cjk = 0;
+  let mut thai = 0;
+
+  for character in text.chars().filter(|c| c.is_alphabetic()) {
+    letters += 1;
+    match script_of(character) {
+      Some(Script::Cjk) => cjk += 1,
+      Some(Script::Thai) => thai += 1,
+      None => {}
+    }
+  }
+
+  if letters == 0 || (cjk + thai) * 2 <= letters {
+    return None;
+  }
+
+  let (language, script_letters) = if thai > cjk {
+    (Language::Thai, thai)
+  } else {
+    (detect_cjk_language(text), cjk)
+  };
+  let confidence = script_letters as f64 / letters as f64;
+
+  Some(LanguageGuess {
+    language,
+    confidence,
+    scores: vec![LanguageScore {
+      language,
+      score: confidence,
+    }],
+  })
+}
+
+/// Guess the language of a text.
+///
+/// Texts in Chinese, Japanese, or Thai are detected by their script.
+/// All other texts are compared with the trigram profiles
+/// stored in the `languages` directory.
+/// The confidence is the relative distance between the best and
+/// the second best matching language (0 = undecided, 1 = certain).
+/// Returns `None` for texts without any letters.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::language::Language;
+/// use textalyzer::language_detection::detect_language;
+///
+/// let guess = detect_language(
+///   "Die Stadt liegt an einem großen Fluss, der im Sommer wenig Wasser führt.",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(guess.language, Language::German);
+/// ```
+pub fn detect_language(text: &str) -> Option<LanguageGuess> {
+  if let Some(guess) = detect_language_by_script(text) {
+    return Some(guess);
+  }
+
+  let text_profile = trigram_profile(text);
+  if text_profile.is_empty() {
+    return None;
+  }
+
+  let mut distances: Vec<(Language, f64)> = Language::ALL
+    .into_iter()
+    .filter_map(|language| {
+      let profile = parse_profile(language.trigram_profile()?);
+      Some((language, profile_distance(&text_profile, &profile)))
+    })
+    .collect();
+  distances.sort_by(|a, b| a.1.total_cmp(&b.1));
+
+  let (language, best_distance) = distances[0];
+  let confidence = match distances.get(1) {
+    Some((_, second_distance)) if *second_distance > 0.0 => {
+      (second_distance - best_distance) / second_distance
+    }
+    _ => 1.0,
+  };
+
+  Some(LanguageGuess {
+    language,
+    confidence,
+    scores: distances
+      .into_iter()
+      .map(|(language, distance)| LanguageScore {
+        language,
+        score: 1.0 - distance,
+      })
+      .collect(),
+  })
+}
+
+/// Guess the language of most of the given texts.
+/// Each text is detected separately and weighted by its length.
+pub fn detect_dominant_language<'a>(
+  texts: impl IntoIterator<Item = &'a str>,
+) -> Option<Language> {
+  let mut weights: HashMap<Language, usize> = HashMap::new();
+  for text in texts {
+    if let Some(guess) = detect_language(text) {
+      *weights.entry(guess.language).or_insert(0) += text.len();
+    }
+  }
+
+  Language::ALL
+    .into_iter()
+    .filter(|language| weights.contains_key(language))
+    .max_by_key(|language| weights[language])
+}
+
+/// Format the detected languages of several files as a table.
+pub fn format_language_guesses(
+  guesses: &[(String, Option<LanguageGuess>)],
+) -> String {
+  let path_width = guesses
+    .iter()
+    .map(|(path, _)| path.chars().count())
+    .max()
+    .unwrap_or(0)
+    .max("Path".len());
+
+  let mut result = format!(
+    "{}  Language  Confidence\n",
+    "Path".pad_to_width_with_alignment(path_width, Alignment::Left)
+  );
+
+  for (path, guess) in guesses {
+    let (language, confidence) = match guess {
+      Some(guess) => (
+        guess.language.name(),
+        format!("{:.0}%", guess.confidence * 100.0),
+      ),
+      None => ("unknown", "-".to_string()),
+    };
+    result += &format!(
+      "{}  {}  {}\n",
+      path.pad_to_width_with_alignment(path_width, Alignment::Left),
+      language.pad_to_width_with_alignment("Language".len(), Alignment::Left),
+      confidence
+        .pad_to_width_with_alignment("Confidence".len(), Alignment::Right),
+    );
+  }
+
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn detected(text: &str) -> Language {
+    detect_language(text).unwrap().language
+  }
+
+  #[test]
+  fn test_detect_language() {
+    assert_eq!(
+      detected("The quick brown fox jumps over the lazy dog and runs away."),
+      Language::English
+    );
+    assert_eq!(
+      detected("Le renard brun rapide saute par-dessus le chien paresseux."),
+      Language::French
+    );
+    assert_eq!(
+      detected("El rápido zorro marrón salta sobre el perro perezoso."),
+      Language::Spanish
+    );
+    assert_eq!(
+      detected("Der schnelle braune Fuchs springt über den faulen Hund."),
+      Language::German
+    );
+  }
+
+  #[test]
+  fn test_detect_language_by_script() {
+    assert_eq!(detected("我们喜欢学习中文。"), Language::Chinese);
+    assert_eq!(detected("私は学生です。"), Language::Japanese);
+    assert_eq!(detected("สวัสดีครับ"), Language::Thai);
+  }
+
+  #[test]
+  fn test_detect_language_without_letters() {
+    assert!(detect_language("1234 !?").is_none());
+  }
+
+  #[test]
+  fn test_detect_dominant_language() {
+    let texts = [
+      "Der schnelle braune Fuchs springt über den faulen Hund.",
+      "The dog sleeps.",
+      "1234",
+    ];
+
+    assert_eq!(detect_dominant_language(texts), Some(Language::German));
+    assert_eq!(detect_dominant_language(["!?"]), None);
+  }
+
+  #[test]
+  fn test_format_language_guesses() {
+    let guesses = vec![
+      (
+        "a.txt".to_string(),
+        detect_language("Der Hund und die Katze."),
+      ),
+      ("numbers.txt".to_string(), None),
+    ];
+    let formatted = format_language_guesses(&guesses);
+    let lines: Vec<&str> = formatted.lines().collect();
+
+    assert_eq!(lines[0], "Path         Language  Confidence");
+    assert!(lines[1].starts_with("a.txt        german    "));
+    assert_eq!(lines[2], "numbers.txt  unknown            -");
+  }
+
+  #[test]
+  fn test_confidence() {
+    let guess =
+      detect_language(include_str!("../../examples/herr_von_ribbeck.txt"))
+        .unwrap();
+
+    assert_eq!(guess.language, Language::German);
+    assert!(guess.confidence > 0.0 && guess.confidence <= 1.0);
+    assert_eq!(guess.scores[0].language, Language::German);
+  }
+}
@@ -0,0 +1,588 @@
+use std::path::Path;
+
+/// Category of a source code token
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum TokenKind {
+  Identifier,
+  Keyword,
+  Number,
+  /// String or character literal including its delimiters
+  String,
+  Comment,
+  /// Any other single character like an operator or a bracket
+  Punctuation,
+}
+
+/// Token of source code
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Token<'a> {
+  pub kind: TokenKind,
+  pub text: &'a str,
+  /// Byte offset of the token in the source
+  pub offset: usize,
+  /// Line of the first character of the token
+  pub line: u32,
+}
+
+/// Lexical rules of a family of programming languages
+#[derive(Debug)]
+pub struct Syntax {
+  pub name: &'static str,
+  /// File extensions (without the dot) of the languages
+  pub extensions: &'static [&'static str],
+  pub line_comments: &'static [&'static str],
+  /// Start and end delimiters of block comments
+  pub block_comments: &'static [(&'static str, &'static str)],
+  /// Delimiters of strings which can span several lines,
+  /// e.g. triple quotes or backticks
+  pub multiline_strings: &'static [&'static str],
+  /// Delimiters of strings which end at the end of a line
+  pub strings: &'static [char],
+  /// Delimiter of literals with exactly one (possibly escaped) character.
+  /// Other occurrences are lexed as punctuation, e.g. Rust lifetimes.
+  pub char_literal: Option<char>,
+  pub keywords: &'static [&'static str],
+  /// Beginnings of lines with import statements
+  pub imports: &'static [&'static str],
+}
+
+pub const RUST: Syntax = Syntax {
+  name: "Rust",
+  extensions: &["rs"],
+  line_comments: &["//"],
+  block_comments: &[("/*", "*/")],
+  multiline_strings: &[],
+  strings: &['"'],
+  char_literal: Some('\''),
+  keywords: &[
+    "as", "async", "await", "break", "const", "continue", "crate", "dyn",
+    "else", "enum", "extern", "false", "fn", "for", "if", "impl", "in", "let",
+    "loop", "match", "mod", "move", "mut", "pub", "ref", "return", "self",
+    "Self", "static", "struct", "super", "trait", "true", "type", "unsafe",
+    "use", "where", "while",
+  ],
+  imports: &["use", "pub use", "pub(crate) use", "extern crate"],
+};
+
+pub const C_LIKE: Syntax = Syntax {
+  name: "C-like",
+  extensions: &[
+    "c", "h", "cc", "cpp", "cxx", "hh", "hpp", "hxx", "java", "cs", "go", "kt",
+    "kts", "scala", "swift",
+  ],
+  line_comments: &["//"],
+  block_comments: &[("/*", "*/")],
+  multiline_strings: &["\"\"\"", "`"],
+  strings: &['"'],
+  char_literal: Some('\''),
+  keywords: &[
+    "abstract",
+    "break",
+    "case",
+    "catch",
+    "char",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "defer",
+    "do",
+    "double",
+    "else",
+    "enum",
+    "extends",
+    "false",
+    "final",
+    "finally",
+    "float",
+    "for",
+    "fun",
+    "func",
+    "go",
+    "if",
+    "implements",
+    "import",
+    "int",
+    "interface",
+    "long",
+    "namespace",
+    "new",
+    "null",
+    "package",
+    "private",
+    "protected",
+    "public",
+    "return",
+    "static",
+    "struct",
+    "switch",
+    "this",
+    "throw",
+    "throws",
+    "true",
+    "try",
+    "typedef",
+    "unsigned",
+    "using",
+    "val",
+    "var",
+    "void",
+    "while",
+  ],
+  imports: &["#include", "#import", "import", "using", "package"],
+};
+
+pub const JAVASCRIPT: Syntax = Syntax {
+  name: "JavaScript",
+  extensions: &["js", "jsx", "mjs", "cjs", "ts", "tsx", "mts", "cts", "dart"],
+  line_comments: &["//"],
+  block_comments: &[("/*", "*/")],
+  multiline_strings: &["`"],
+  strings: &['"', '\''],
+  char_literal: None,
+  keywords: &[
+    "async",
+    "await",
+    "break",
+    "case",
+    "catch",
+    "class",
+    "const",
+    "continue",
+    "default",
+    "delete",
+    "do",
+    "else",
+    "export",
+    "extends",
+    "false",
+    "finally",
+    "for",
+    "from",
+    "function",
+    "if",
+    "import",
+    "in",
+    "instanceof",
+    "interface",
+    "let",
+    "new",
+    "null",
+    "of",
+    "return",
+    "super",
+    "switch",
+    "this",
+    "throw",
+    "true",
+    "try",
+    "type",
+    "typeof",
+    "undefined",
+    "var",
+    "void",
+    "while",
+    "yield",
+  ],
+  imports: &["import"],
+};
+
+pub const PYTHON: Syntax = Syntax {
+  name: "Python",
+  extensions: &["py", "pyi"],
+  line_comments: &["#"],
+  block_comments: &[],
+  multiline_strings: &["\"\"\"", "'''"],
+  strings: &['"', '\''],
+  char_literal: None,
+  keywords: &[
+    "and", "as", "assert", "async", "await", "break", "class", "continue",
+    "def", "del", "elif", "else", "except", "False", "finally", "for", "from",
+    "global", "if", "import", "in", "is", "lambda", "None", "nonlocal", "not",
+    "or", "pass", "raise", "return", "True", "try", "while", "with", "yield",
+  ],
+  imports: &["import", "from"],
+};
+
+pub const HASKELL: Syntax = Syntax {
+  name: "Haskell",
+  extensions: &["hs", "lhs", "elm", "purs"],
+  line_comments: &["--"],
+  block_comments: &[("{-", "-}")],
+  multiline_strings: &[],
+  strings: &['"'],
+  char_literal: Some('\''),
+  keywords: &[
+    "case", "class", "data", "deriving", "do", "else", "if", "import", "in",
+    "instance", "let", "module", "newtype", "of", "then", "type", "where",
+  ],
+  imports: &["import"],
+};
+
+pub const SQL: Syntax = Syntax {
+  name: "SQL",
+  extensions: &["sql"],
+  line_comments: &["--"],
+  block_comments: &[("/*", "*/")],
+  multiline_strings: &[],
+  strings: &['\'', '"'],
+  char_literal: None,
+  keywords: &[],
+  imports: &[],
+};
+
+pub const LUA: Syntax = Syntax {
+  name: "Lua",
+  extensions: &["lua"],
+  line_comments: &["--"],
+  block_comments: &[("--[[", "]]")],
+  multiline_strings: &[],
+  strings: &['"', '\''],
+  char_literal: None,
+  keywords: &[
+    "and", "break", "do", "else", "elseif", "end", "false", "for", "function",
+    "if", "in", "local", "nil", "not", "or", "repeat", "return", "then",
+    "true", "until", "while",
+  ],
+  imports: &[],
+};
+
+/// Shell scripts and other languages with `#` comments
+pub const SCRIPT: Syntax = Syntax {
+  name: "Script",
+  extensions: &[
+    "sh", "bash", "zsh", "fish", "rb", "pl", "pm", "r", "ex", "exs", "nix",
+    "yaml", "yml", "toml", "cmake",
+  ],
+  line_comments: &["#"],
+  block_comments: &[],
+  multiline_strings: &[],
+  strings: &['"', '\''],
+  char_literal: None,
+  keywords: &[],
+  imports: &["require", "require_relative", "source"],
+};
+
+pub const MARKUP: Syntax = Syntax {
+  name: "Markup",
+  extensions: &["html", "htm", "xml", "svg", "vue", "md", "markdown"],
+  line_comments: &[],
+  block_comments: &[("<!--", "-->")],
+  multiline_strings: &[],
+  strings: &['"'],
+  char_literal: None,
+  keywords: &[],
+  imports: &[],
+};
+
+pub const CSS: Syntax = Syntax {
+  name: "CSS",
+  extensions: &["css", "scss", "sass", "less"],
+  line_comments: &[],
+  block_comments: &[("/*", "*/")],
+  multiline_strings: &[],
+  strings: &['"', '\''],
+  char_literal: None,
+  keywords: &[],
+  imports: &["@import"],
+};
+
+/// Fallback for files of unknown languages,
+/// which are only split into words, numbers, and punctuation
+pub const PLAIN_TEXT: Syntax = Syntax {
+  name: "Plain text",
+  extensions: &[],
+  line_comments: &[],
+  block_comments: &[],
+  multiline_strings: &[],
+  strings: &[],
+  char_literal: None,
+  keywords: &[],
+  imports: &[],
+};
+
+pub const SYNTAXES: &[&Syntax] = &[
+  &RUST,
+  &C_LIKE,
+  &JAVASCRIPT,
+  &PYTHON,
+  &HASKELL,
+  &SQL,
+  &LUA,
+  &SCRIPT,
+  &MARKUP,
+  &CSS,
+];
+
+/// Select the syntax for a file by its extension.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::lexer::syntax_for_path;
+///
+/// assert_eq!(syntax_for_path("src/main.rs").name, "Rust");
+/// assert_eq!(syntax_for_path("App.TSX").name, "JavaScript");
+/// assert_eq!(syntax_for_path("notes.txt").name, "Plain text");
+/// ```
+pub fn syntax_for_path(path: &str) -> &'static Syntax {
+  let extension = Path::new(path)
+    .extension()
+    .and_then(|extension| extension.to_str())
+    .map(str::to_lowercase)
+    .unwrap_or_default();
+
+  SYNTAXES
+    .iter()
+    .find(|syntax| syntax.extensions.contains(&extension.as_str()))
+    .copied()
+    .unwrap_or(&PLAIN_TEXT)
+}
+
+pub(crate) fn is_word_char(character: char) -> bool {
+  character.is_alphanumeric() || character == '_'
+}
+
+/// Byte length of a string literal starting with `delimiter`,
+/// including both delimiters.
+/// Unterminated literals end at the end of the line
+/// or for multi-line strings at the end of the text.
+fn string_length(rest: &str, delimiter: &str, multiline: bool) -> usize {
+  let mut chars = rest.char_indices().skip(delimiter.chars().count());
+  while let Some((idx, character)) = chars.next() {
+    if character == '\\' {
+      chars.next();
+    } else if rest[idx..].starts_with(delimiter) {
+      return idx + delimiter.len();
+    } else if character == '\n' && !multiline {
+      return idx;
+    }
+  }
+  rest.len()
+}
+
+/// Byte length of a character literal like `'a'` or `'\n'`
+/// or `None` if the delimiter doesn't start one.
+fn char_literal_length(rest: &str, delimiter: char) -> Option<usize> {
+  let mut chars = rest.char_indices().skip(1);
+  let (_, first) = chars.next()?;
+  if first == '\\' {
+    // Escapes like '\n', '\'', or '\u{1F600}'
+    chars.next()?;
+    return chars
+      .take(10)
+      .find(|(_, character)| *character == delimiter)
+      .map(|(idx, _)| idx + delimiter.len_utf8());
+  }
+  if first == delimiter || first == '\n' {
+    return None;
+  }
+  match chars.next()? {
+    (idx, character) if character == delimiter => {
+      Some(idx + delimiter.len_utf8())
+    }
+    _ => None,
+  }
+}
+
+/// Split source code into tokens according to the rules of its syntax.
+/// Whitespace is skipped, comments are kept as tokens.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::lexer::{lex, TokenKind, RUST};
+///
+/// let tokens = lex("let x = \"a b\"; // Note", &RUST);
+/// let kinds: Vec<TokenKind> = tokens.iter().map(|token| token.kind).collect();
+///
+/// assert_eq!(
+///   kinds,
+///   [
+///     TokenKind::Keyword,
+///     TokenKind::Identifier,
+///     TokenKind::Punctuation,
+///     TokenKind::String,
+///     TokenKind::Punctuation,
+///     TokenKind::Comment,
+///   ]
+/// );
+/// assert_eq!(tokens[3].text, "\"a b\"");
+/// ```
+pub fn lex<'a>(text: &'a str, syntax: &Syntax) -> Vec<Token<'a>> {
+  let mut tokens = Vec::new();
+  let mut line = 1;
+  let mut offset = 0;
+
+  while let Some(character) = text[offset..].chars().next() {
+    let rest = &text[offset..];
+
+    if character.is_whitespace() {
+      if character == '\n' {
+        line += 1;
+      }
+      offset += character.len_utf8();
+      continue;
+    }
+
+    let block_comment = syntax
+      .block_comments
+      .iter()
+      .find(|(start, _)| rest.starts_with(start));
+    let (kind, length) = if let Some((start, end)) = block_comment {
+      let length = rest[start.len()..]
+        .find(end)
+        .map(|idx| start.len() + idx + end.len())
+        .unwrap_or(rest.len());
+      (TokenKind::Comment, length)
+    } else if syntax
+      .line_comments
+      .iter()
+      .any(|start| rest.starts_with(start))
+    {
+      (TokenKind::Comment, rest.find('\n').unwrap_or(rest.len()))
+    } else if let Some(delimiter) = syntax
+      .multiline_strings
+      .iter()
+      .find(|delimiter| rest.starts_with(*delimiter))
+    {
+      (TokenKind::String, string_length(rest, delimiter, true))
+    } else if syntax.strings.contains(&character) {
+      let delimiter = &rest[..character.len_utf8()];
+      (TokenKind::String, string_length(rest, delimiter, false))
+    } else if let Some(length) = syntax
+      .char_literal
+      .filter(|delimiter| *delimiter == character)
+      .and_then(|delimiter| char_literal_length(rest, delimiter))
+    {
+      (TokenKind::String, length)
+    } else if character.is_numeric() {
+      let length = rest
+        .find(|character: char| !is_word_char(character) && character != '.')
+        .unwrap_or(rest.len());
+      (TokenKind::Number, length)
+    } else if is_word_char(character) {
+      let length = rest
+        .find(|character: char| !is_word_char(character))
+        .unwrap_or(rest.len());
+      let kind = if syntax.keywords.contains(&&rest[..length]) {
+        TokenKind::Keyword
+      } else {
+        TokenKind::Identifier
+      };
+      (kind, length)
+    } else {
+      (TokenKind::Punctuation, character.len_utf8())
+    };
+
+    let token_text = &rest[..length];
+    tokens.push(Token {
+      kind,
+      text: token_text,
+      offset,
+      line,
+    });
+    line += token_text.matches('\n').count() as u32;
+    offset += length;
+  }
+
+  tokens
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn kinds_and_texts<'a>(
+    text: &'a str,
+    syntax: &Syntax,
+  ) -> Vec<(TokenKind, &'a str)> {
+    lex(text, syntax)
+      .into_iter()
+      .map(|token| (token.kind, token.text))
+      .collect()
+  }
+
+  #[test]
+  fn test_lex_rust_chars_and_lifetimes() {
+    use TokenKind::*;
+    assert_eq!(
+      kinds_and_texts("fn f<'a>(c: &'a char) { '\\n' }", &RUST),
+      [
+        (Keyword, "fn"),
+        (Identifier, "f"),
+        (Punctuation, "<"),
+        (Punctuation, "'"),
+        (Identifier, "a"),
+        (Punctuation, ">"),
+        (Punctuation, "("),
+        (Identifier, "c"),
+        (Punctuation, ":"),
+        (Punctuation, "&"),
+        (Punctuation, "'"),
+        (Identifier, "a"),
+        (Identifier, "char"),
+        (Punctuation, ")"),
+        (Punctuation, "{"),
+        (String, "'\\n'"),
+        (Punctuation, "}"),
+      ]
+    );
+  }
+
+  #[test]
+  fn test_lex_comments_and_lines() {
+    let tokens = lex("a /* one\ntwo */ b\n// three\nc", &C_LIKE);
+    let lines: Vec<(&str, u32)> = tokens
+      .iter()
+      .map(|token| (token.text, token.line))
+      .collect();
+
+    assert_eq!(
+      lines,
+      [
+        ("a", 1),
+        ("/* one\ntwo */", 1),
+        ("b", 2),
+        ("// three", 3),
+        ("c", 4)
+      ]
+    );
+  }
+
+  #[test]
+  fn test_lex_strings() {
+    use TokenKind::*;
+    assert_eq!(
+      kinds_and_texts("x = \"\"\"a\n\"b\" \"\"\" + 'c\\'d' # e", &PYTHON),
+      [
+        (Identifier, "x"),
+        (Punctuation, "="),
+        (String, "\"\"\"a\n\"b\" \"\"\""),
+        (Punctuation, "+"),
+        (String, "'c\\'d'"),
+        (Comment, "# e"),
+      ]
+    );
+    // Unterminated strings end at the end of the line
+    assert_eq!(
+      kinds_and_texts("\"abc\nd", &JAVASCRIPT),
+      [(String, "\"abc"), (Identifier, "d")]
+    );
+  }
+
+  #[test]
+  fn test_lex_numbers() {
+    use TokenKind::*;
+    assert_eq!(
+      kinds_and_texts("x+1.5e3-0xFF", &PLAIN_TEXT),
+      [
+        (Identifier, "x"),
+        (Punctuation, "+"),
+        (Number, "1.5e3"),
+        (Punctuation, "-"),
+        (Number, "0xFF"),
+      ]
+    );
+  }
+}
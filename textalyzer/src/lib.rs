@@ -1,54 +1,207 @@
+pub mod baseline;
+pub mod char_frequency;
+pub mod collocations;
+pub mod concordance;
+pub mod diversity;
 pub mod duplication;
+pub mod duplication_report;
 pub mod file_utils;
 pub mod frequency;
+pub mod histogram;
+pub mod keyness;
+pub mod keywords;
+pub mod language;
+pub mod language_detection;
+pub mod lexer;
 pub mod line_length;
 pub mod output;
+pub mod readability;
+pub mod segmenter;
+pub mod significance;
+pub mod similarity;
+pub mod source_filter;
+pub mod stats;
+pub mod thresholds;
+pub mod token_duplication;
+pub mod tokenizer;
 pub mod types;
+pub mod zipf;
 
 extern crate colored;
 extern crate ignore;
 extern crate memmap2;
 extern crate pad;
 extern crate rayon;
+extern crate rust_stemmers;
 extern crate terminal_size;
+extern crate unicode_segmentation;
 extern crate unicode_width;
 
 use colored::Colorize;
+use std::collections::{HashMap, HashSet};
 use std::error::Error;
-use std::fs;
 use std::io::Write;
 use std::path::Path;
 
+use baseline::{load_baseline, remove_known, save_baseline};
+use char_frequency::{
+  compare_char_frequency, count_file_letters, format_char_frequency,
+  parse_reference_table,
+};
+use collocations::{find_file_collocations, format_collocations};
+use concordance::{find_file_concordances, format_concordances};
+use diversity::{calculate_file_diversity, format_diversity};
 use duplication::{find_duplicate_lines, find_multi_line_duplications};
-use file_utils::{find_all_files, load_files};
-use frequency::{format_freq_map, generate_frequency_map};
+use duplication_report::{build_report, format_report, non_empty_line_numbers};
+use file_utils::resolve_paths;
+use file_utils::{find_all_files, load_files, load_paths_or_stdin};
+use frequency::{format_freq_selection, select_frequencies};
+use frequency::{
+  generate_file_frequency_map, generate_file_surface_forms, FrequencyOptions,
+};
+use frequency::{sort_surface_forms, OTHERS_LABEL};
+use histogram::{fit_to_terminal, terminal_width};
+use keyness::{compare_frequencies, format_comparison};
+use keywords::{extract_file_keywords, format_keywords};
+use language::{load_stop_words, Language};
+use language_detection::format_language_guesses;
+use language_detection::{detect_dominant_language, detect_language};
 use line_length::process_and_output_line_length;
-use output::output_duplications;
-use types::{Command, Config, FrequencyItem};
-use types::{DuplicationItem, DuplicationLocation};
+use output::{duplication_items, output_duplications};
+use output::{output_baseline_skipped, output_baseline_written};
+use output::{output_duplication_summary, output_similar_duplications};
+use readability::{calculate_readability, count_text, format_readability};
+use similarity::find_similar_blocks;
+use source_filter::filter_files;
+use stats::{calculate_file_stats, format_stats};
+use thresholds::check_thresholds;
+use thresholds::DuplicationSummary;
+use token_duplication::{find_token_duplications, DEFAULT_MIN_TOKENS};
+use types::{Command, Config, FrequencyItem, NGramFrequencyItem};
+use types::{DiversityItem, DuplicationItem, DuplicationsWithReport};
+use types::{LanguageDetectionItem, ReadabilityItem};
+use types::{StatsItem, StatsReport, ThresholdOptions, TokenizerOptions};
+use zipf::{analyze_zipf, format_zipf, format_zipf_csv, plot_zipf};
+
+/// Show the summary and fail if a threshold is exceeded
+fn enforce_thresholds<A: Write>(
+  summary: Option<DuplicationSummary>,
+  thresholds: &ThresholdOptions,
+  json: bool,
+  output_stream: A,
+) -> Result<(), Box<dyn Error>> {
+  let Some(summary) = summary.filter(|_| !thresholds.is_empty()) else {
+    return Ok(());
+  };
+  if !json {
+    output_duplication_summary(&summary, output_stream)?;
+  }
+  check_thresholds(summary, thresholds)?;
+  Ok(())
+}
 
 pub fn run<A: Write>(
   config: Config,
   mut output_stream: A,
 ) -> Result<(), Box<dyn Error>> {
   match config.command {
-    Command::Histogram { filepath, json } => {
-      let file_content = fs::read_to_string(filepath)?;
-      let freq_map = generate_frequency_map(&file_content);
+    Command::Histogram {
+      paths,
+      ngram,
+      stop_words,
+      stem,
+      lang,
+      show_forms,
+      tokenizer,
+      histogram,
+      bars,
+      json,
+    } => {
+      if ngram == 0 {
+        return Err("The n-gram size must be at least 1".into());
+      }
+      let language: Option<Language> =
+        lang.map(|lang| lang.parse()).transpose()?;
+      let file_entries = load_paths_or_stdin(&paths)?;
+      // Only detect the language if it is needed for stemming
+      let stem_language = stem.then(|| {
+        language
+          .or_else(|| {
+            detect_dominant_language(
+              file_entries.iter().filter_map(|file| file.content.as_str()),
+            )
+          })
+          .unwrap_or(Language::English)
+      });
+      if let Some(stem_language) = stem_language
+        .filter(|stem_language| stem_language.stemming_algorithm().is_none())
+      {
+        return Err(
+          format!("Stemming is not supported for {stem_language}").into(),
+        );
+      }
+      let options = FrequencyOptions {
+        ngram,
+        stop_words: match stop_words {
+          Some(lang_or_file) => load_stop_words(&lang_or_file)?,
+          None => HashSet::new(),
+        },
+        stem: stem_language,
+        tokenizer: TokenizerOptions {
+          language,
+          ..tokenizer
+        },
+      };
+      let freq_map = generate_file_frequency_map(&file_entries, &options);
+      let forms_map = if show_forms {
+        generate_file_surface_forms(&file_entries, &options)
+      } else {
+        HashMap::new()
+      };
+
+      let selection = select_frequencies(freq_map, &histogram);
 
       if json {
-        // Convert HashMap to Vec<FrequencyItem> for stable JSON output
-        let mut freq_vec: Vec<FrequencyItem> = freq_map
-          .into_iter()
-          .map(|(word, count)| FrequencyItem { word, count })
+        let mut freq_vec: Vec<FrequencyItem> = selection
+          .entries
+          .iter()
+          .map(|(word, count)| FrequencyItem {
+            word: word.clone(),
+            count: *count,
+            forms: forms_map.get(word).map(sort_surface_forms),
+            others: None,
+          })
           .collect();
-        // Sort by count descending, then alphabetically for stability
-        freq_vec
-          .sort_by(|a, b| b.count.cmp(&a.count).then(a.word.cmp(&b.word)));
-        let json_output = serde_json::to_string_pretty(&freq_vec)?;
+        if let Some((entries, count)) = selection.others {
+          freq_vec.push(FrequencyItem {
+            word: OTHERS_LABEL.to_string(),
+            count,
+            forms: None,
+            others: Some(entries),
+          });
+        }
+
+        let json_output = if ngram > 1 {
+          let ngram_vec: Vec<NGramFrequencyItem> = freq_vec
+            .into_iter()
+            .map(|item| NGramFrequencyItem {
+              ngram: match item.others {
+                Some(_) => vec![item.word],
+                None => item.word.split(' ').map(String::from).collect(),
+              },
+              count: item.count,
+              forms: item.forms,
+              others: item.others,
+            })
+            .collect();
+          serde_json::to_string_pretty(&ngram_vec)?
+        } else {
+          serde_json::to_string_pretty(&freq_vec)?
+        };
         writeln!(&mut output_stream, "{json_output}")?;
       } else {
-        let formatted = format_freq_map(freq_map);
+        let bars = fit_to_terminal(bars);
+        let formatted = format_freq_selection(&selection, &forms_map, &bars);
         // Use instead writeln! of println! to avoid "broken pipe" errors
         writeln!(&mut output_stream, "{formatted}")?;
       }
@@ -57,9 +210,27 @@ pub fn run<A: Write>(
     Command::Duplication {
       paths,
       min_lines,
+      similarity,
+      min_tokens,
+      normalize,
+      skip,
+      baseline,
+      write_baseline,
+      thresholds,
+      report,
       files_only,
       json,
     } => {
+      if similarity.is_some_and(|threshold| !(0.0..=1.0).contains(&threshold)) {
+        return Err("The similarity threshold must be between 0 and 1".into());
+      }
+      if thresholds
+        .max_percentage
+        .is_some_and(|percentage| !(0.0..=100.0).contains(&percentage))
+      {
+        return Err("The maximum percentage must be between 0 and 100".into());
+      }
+
       // Collect all file entries from all specified paths
       let mut all_files = Vec::new();
       let mut scanned_dirs = 0;
@@ -76,16 +247,18 @@ pub fn run<A: Write>(
         } else if path.is_dir() {
           // Directory traversal
           let files = find_all_files(path)?;
-          writeln!(
-            &mut output_stream,
-            "{}",
-            format!(
-              "🔎 Scanning {} files in directory: {}",
-              files.len(),
-              path.display()
-            )
-            .bold()
-          )?;
+          if !json {
+            writeln!(
+              &mut output_stream,
+              "{}",
+              format!(
+                "🔎 Scanning {} files in directory: {}",
+                files.len(),
+                path.display()
+              )
+              .bold()
+            )?;
+          }
 
           all_files.extend(files);
           scanned_dirs += 1;
@@ -96,7 +269,7 @@ pub fn run<A: Write>(
         }
       }
 
-      if scanned_dirs == 0 && scanned_files > 0 {
+      if !json && scanned_dirs == 0 && scanned_files > 0 {
         writeln!(
           &mut output_stream,
           "{}",
@@ -109,10 +282,78 @@ pub fn run<A: Write>(
       }
 
       // Load all collected files
-      let file_entries = load_files(all_files)?;
+      let file_entries = filter_files(load_files(all_files)?, &skip);
+      // The thresholds are checked against the lines covered in the report
+      let file_lines = (report || !thresholds.is_empty())
+        .then(|| non_empty_line_numbers(&file_entries));
+
+      if let Some(threshold) = similarity {
+        let mut similar =
+          find_similar_blocks(&file_entries, min_lines, threshold);
+        if let Some(path) = baseline {
+          if write_baseline {
+            let items: Vec<_> = similar
+              .into_iter()
+              .map(|item| DuplicationItem {
+                content: item.content,
+                locations: item.locations,
+              })
+              .collect();
+            save_baseline(&path, &items)?;
+            return output_baseline_written(items.len(), &path, output_stream);
+          }
+          let known = load_baseline(&path)?;
+          let removed = remove_known(&mut similar, &known, |item| {
+            (&item.content, item.locations.len())
+          });
+          if !json {
+            output_baseline_skipped(removed, &mut output_stream)?;
+          }
+        }
+        let coverage = file_lines.map(|file_lines| {
+          build_report(
+            &file_lines,
+            similar
+              .iter()
+              .flat_map(|item| &item.locations)
+              .map(|location| {
+                (location.path.as_str(), location.line, location.end_line)
+              }),
+          )
+        });
+        let summary = coverage.as_ref().map(|coverage| DuplicationSummary {
+          duplications: similar.len(),
+          lines: coverage.total,
+        });
+        let report = coverage.filter(|_| report);
+        if json {
+          let json_output = match report {
+            Some(report) => {
+              serde_json::to_string_pretty(&DuplicationsWithReport {
+                duplications: similar,
+                report,
+              })?
+            }
+            None => serde_json::to_string_pretty(&similar)?,
+          };
+          writeln!(&mut output_stream, "{json_output}")?;
+        } else {
+          output_similar_duplications(similar, &mut output_stream, files_only)?;
+          if let Some(report) = report {
+            writeln!(&mut output_stream, "\n{}", format_report(&report))?;
+          }
+        }
+        return enforce_thresholds(summary, &thresholds, json, output_stream);
+      }
 
       // Choose the appropriate function based on the min_lines value
-      let duplications = if min_lines <= 1 {
+      let mut duplications = if min_tokens.is_some() || normalize {
+        find_token_duplications(
+          file_entries,
+          min_tokens.unwrap_or(DEFAULT_MIN_TOKENS),
+          normalize,
+        )
+      } else if min_lines <= 1 {
         // For min_lines of 1, use the single-line detection function
         find_duplicate_lines(file_entries)
       } else {
@@ -131,25 +372,57 @@ pub fn run<A: Write>(
         results
       };
 
+      if let Some(path) = baseline {
+        if write_baseline {
+          let items = duplication_items(duplications);
+          save_baseline(&path, &items)?;
+          return output_baseline_written(items.len(), &path, output_stream);
+        }
+        let known = load_baseline(&path)?;
+        let removed =
+          remove_known(&mut duplications, &known, |(content, locations)| {
+            (content, locations.len())
+          });
+        if !json {
+          output_baseline_skipped(removed, &mut output_stream)?;
+        }
+      }
+
+      let coverage = file_lines.map(|file_lines| {
+        build_report(
+          &file_lines,
+          duplications
+            .iter()
+            .flat_map(|(_, locations)| locations)
+            .map(|(path, line, end_line)| (path.as_str(), *line, *end_line)),
+        )
+      });
+      let summary = coverage.as_ref().map(|coverage| DuplicationSummary {
+        duplications: duplications.len(),
+        lines: coverage.total,
+      });
+      let report = coverage.filter(|_| report);
       if json {
-        let semantic: Vec<DuplicationItem> = duplications
-          .into_iter()
-          .map(|(content, locs)| DuplicationItem {
-            content,
-            locations: locs
-              .into_iter()
-              .map(|(path, line)| DuplicationLocation { path, line })
-              .collect(),
-          })
-          .collect();
-        let json_output = serde_json::to_string_pretty(&semantic)?;
+        let semantic = duplication_items(duplications);
+        let json_output = match report {
+          Some(report) => {
+            serde_json::to_string_pretty(&DuplicationsWithReport {
+              duplications: semantic,
+              report,
+            })?
+          }
+          None => serde_json::to_string_pretty(&semantic)?,
+        };
         writeln!(&mut output_stream, "{json_output}")?;
-        Ok(())
       } else {
-        output_duplications(duplications, output_stream, files_only)
+        output_duplications(duplications, &mut output_stream, files_only)?;
+        if let Some(report) = report {
+          writeln!(&mut output_stream, "\n{}", format_report(&report))?;
+        }
       }
+      enforce_thresholds(summary, &thresholds, json, output_stream)
     }
-    Command::LineLength { paths, json } => {
+    Command::LineLength { paths, bars, json } => {
       // Collect all file entries from all specified paths
       let mut all_files = Vec::new();
       let mut scanned_dirs = 0;
@@ -202,7 +475,307 @@ pub fn run<A: Write>(
       let file_entries = load_files(all_files)?;
 
       // Process and output the line length histogram
-      process_and_output_line_length(file_entries, output_stream, json)
+      let bars = fit_to_terminal(bars);
+      process_and_output_line_length(file_entries, output_stream, json, &bars)
+    }
+    Command::Readability { paths, json } => {
+      let file_entries = load_files(resolve_paths(&paths)?)?;
+
+      let reports: Vec<_> = file_entries
+        .iter()
+        .filter_map(|file| {
+          let counts = count_text(file.content.as_str()?);
+          let scores = calculate_readability(&counts);
+          Some((file.name.clone(), counts, scores))
+        })
+        .collect();
+
+      if json {
+        let items: Vec<ReadabilityItem> = reports
+          .into_iter()
+          .map(|(path, counts, scores)| ReadabilityItem {
+            path,
+            sentences: counts.sentences,
+            words: counts.words,
+            syllables: counts.syllables,
+            scores,
+          })
+          .collect();
+        let json_output = serde_json::to_string_pretty(&items)?;
+        writeln!(&mut output_stream, "{json_output}")?;
+      } else {
+        let formatted: Vec<String> = reports
+          .iter()
+          .map(|(path, counts, scores)| {
+            format_readability(path, counts, scores)
+          })
+          .collect();
+        writeln!(&mut output_stream, "{}", formatted.join("\n"))?;
+      }
+      Ok(())
+    }
+    Command::Stats { paths, json } => {
+      let file_entries = load_files(resolve_paths(&paths)?)?;
+      let (file_stats, total) = calculate_file_stats(&file_entries);
+
+      if json {
+        let report = StatsReport {
+          files: file_stats
+            .into_iter()
+            .map(|(path, stats)| StatsItem { path, stats })
+            .collect(),
+          total,
+        };
+        let json_output = serde_json::to_string_pretty(&report)?;
+        writeln!(&mut output_stream, "{json_output}")?;
+      } else {
+        let mut formatted: Vec<String> = file_stats
+          .iter()
+          .map(|(path, stats)| format_stats(path, stats))
+          .collect();
+        if file_stats.len() > 1 {
+          formatted.push(format_stats("Total", &total));
+        }
+        writeln!(&mut output_stream, "{}", formatted.join("\n"))?;
+      }
+      Ok(())
+    }
+    Command::Diversity {
+      paths,
+      window,
+      json,
+    } => {
+      let file_entries = load_files(resolve_paths(&paths)?)?;
+      let file_diversity = calculate_file_diversity(&file_entries, window);
+
+      if json {
+        let items: Vec<DiversityItem> = file_diversity
+          .into_iter()
+          .map(|(path, diversity)| DiversityItem { path, diversity })
+          .collect();
+        let json_output = serde_json::to_string_pretty(&items)?;
+        writeln!(&mut output_stream, "{json_output}")?;
+      } else {
+        let formatted: Vec<String> = file_diversity
+          .iter()
+          .map(|(path, diversity)| format_diversity(path, diversity))
+          .collect();
+        writeln!(&mut output_stream, "{}", formatted.join("\n"))?;
+      }
+      Ok(())
+    }
+    Command::Zipf {
+      paths,
+      deviations,
+      csv,
+      plot,
+      width,
+      json,
+    } => {
+      let file_entries = load_paths_or_stdin(&paths)?;
+      let freq_map = generate_file_frequency_map(
+        &file_entries,
+        &FrequencyOptions::default(),
+      );
+
+      if csv {
+        write!(&mut output_stream, "{}", format_zipf_csv(freq_map))?;
+        return Ok(());
+      }
+
+      let report = analyze_zipf(freq_map.clone(), deviations);
+      if json {
+        let json_output = serde_json::to_string_pretty(&report)?;
+        writeln!(&mut output_stream, "{json_output}")?;
+      } else {
+        writeln!(&mut output_stream, "{}", format_zipf(&report))?;
+        if plot {
+          let width = width.unwrap_or_else(terminal_width);
+          writeln!(&mut output_stream, "{}", plot_zipf(freq_map, width))?;
+        }
+      }
+      Ok(())
+    }
+    Command::Keywords {
+      paths,
+      top,
+      stop_words,
+      tokenizer,
+      json,
+    } => {
+      let file_entries = load_files(resolve_paths(&paths)?)?;
+      if file_entries.len() < 2 {
+        return Err("TF-IDF needs at least two documents".into());
+      }
+      let options = FrequencyOptions {
+        stop_words: match stop_words {
+          Some(lang_or_file) => load_stop_words(&lang_or_file)?,
+          None => HashSet::new(),
+        },
+        tokenizer,
+        ..Default::default()
+      };
+      let keywords = extract_file_keywords(&file_entries, &options, top);
+
+      if json {
+        let json_output = serde_json::to_string_pretty(&keywords)?;
+        writeln!(&mut output_stream, "{json_output}")?;
+      } else {
+        let formatted: Vec<String> =
+          keywords.iter().map(format_keywords).collect();
+        writeln!(&mut output_stream, "{}", formatted.join("\n"))?;
+      }
+      Ok(())
+    }
+    Command::Collocations {
+      paths,
+      measure,
+      min_count,
+      top,
+      stop_words,
+      tokenizer,
+      json,
+    } => {
+      let file_entries = load_paths_or_stdin(&paths)?;
+      let options = FrequencyOptions {
+        stop_words: match stop_words {
+          Some(lang_or_file) => load_stop_words(&lang_or_file)?,
+          None => HashSet::new(),
+        },
+        tokenizer,
+        ..Default::default()
+      };
+      let mut collocations =
+        find_file_collocations(&file_entries, &options, measure, min_count);
+      collocations.truncate(top);
+
+      if json {
+        let json_output = serde_json::to_string_pretty(&collocations)?;
+        writeln!(&mut output_stream, "{json_output}")?;
+      } else if collocations.is_empty() {
+        writeln!(
+          &mut output_stream,
+          "No word pairs occur at least {min_count} times."
+        )?;
+      } else {
+        write!(&mut output_stream, "{}", format_collocations(&collocations))?;
+      }
+      Ok(())
+    }
+    Command::CompareFrequency {
+      a,
+      b,
+      measure,
+      top,
+      stop_words,
+      tokenizer,
+      json,
+    } => {
+      let options = FrequencyOptions {
+        stop_words: match stop_words {
+          Some(lang_or_file) => load_stop_words(&lang_or_file)?,
+          None => HashSet::new(),
+        },
+        tokenizer,
+        ..Default::default()
+      };
+      let freq_map_a = generate_file_frequency_map(
+        &load_paths_or_stdin(std::slice::from_ref(&a))?,
+        &options,
+      );
+      let freq_map_b = generate_file_frequency_map(
+        &load_paths_or_stdin(std::slice::from_ref(&b))?,
+        &options,
+      );
+      let comparison =
+        compare_frequencies((&a, &freq_map_a), (&b, &freq_map_b), measure, top);
+
+      if json {
+        let json_output = serde_json::to_string_pretty(&comparison)?;
+        writeln!(&mut output_stream, "{json_output}")?;
+      } else {
+        write!(&mut output_stream, "{}", format_comparison(&comparison))?;
+      }
+      Ok(())
+    }
+    Command::Concordance {
+      word,
+      paths,
+      context,
+      tokenizer,
+      json,
+    } => {
+      let file_entries = load_paths_or_stdin(&paths)?;
+      let items =
+        find_file_concordances(&file_entries, &word, context, &tokenizer);
+
+      if json {
+        let json_output = serde_json::to_string_pretty(&items)?;
+        writeln!(&mut output_stream, "{json_output}")?;
+      } else if items.is_empty() {
+        writeln!(&mut output_stream, "No occurrences of \"{word}\" found.")?;
+      } else {
+        write!(&mut output_stream, "{}", format_concordances(&items))?;
+      }
+      Ok(())
+    }
+    Command::CharFrequency { paths, lang, json } => {
+      let language: Language = lang.parse()?;
+      let reference = match language.character_frequencies() {
+        Some(tsv) => parse_reference_table(tsv)?,
+        None => {
+          return Err(
+            format!("No character frequency table for {language}").into(),
+          )
+        }
+      };
+      let file_entries = load_files(resolve_paths(&paths)?)?;
+      let counts = count_file_letters(&file_entries);
+      let report = compare_char_frequency(&counts, &reference);
+
+      if json {
+        let json_output = serde_json::to_string_pretty(&report)?;
+        writeln!(&mut output_stream, "{json_output}")?;
+      } else {
+        let formatted = format_char_frequency(&report);
+        writeln!(&mut output_stream, "{formatted}")?;
+      }
+      Ok(())
+    }
+    Command::DetectLanguage { paths, json } => {
+      let file_entries = load_files(resolve_paths(&paths)?)?;
+      let guesses: Vec<(String, Option<_>)> = file_entries
+        .iter()
+        .filter_map(|file| {
+          Some((file.name.clone(), detect_language(file.content.as_str()?)))
+        })
+        .collect();
+
+      if json {
+        let items: Vec<LanguageDetectionItem> = guesses
+          .into_iter()
+          .map(|(path, guess)| match guess {
+            Some(guess) => LanguageDetectionItem {
+              path,
+              language: Some(guess.language),
+              confidence: guess.confidence,
+              scores: guess.scores,
+            },
+            None => LanguageDetectionItem {
+              path,
+              language: None,
+              confidence: 0.0,
+              scores: Vec::new(),
+            },
+          })
+          .collect();
+        let json_output = serde_json::to_string_pretty(&items)?;
+        writeln!(&mut output_stream, "{json_output}")?;
+      } else {
+        let formatted = format_language_guesses(&guesses);
+        write!(&mut output_stream, "{formatted}")?;
+      }
+      Ok(())
     }
   }
 }
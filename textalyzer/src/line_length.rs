@@ -1,14 +1,12 @@
+use crate::histogram::{render_histogram, HistogramRow};
 use crate::types::LineLengthItem;
-use crate::types::{FileEntry, MappedContent};
-use pad::{Alignment, PadStr};
+use crate::types::{BarOptions, FileEntry, MappedContent};
 use serde_json;
 use std::collections::HashMap;
 use std::error::Error;
 use std::io::Write;
 use unicode_width::UnicodeWidthStr;
 
-const MAX_LINE_LENGTH_HISTOGRAM_BAR: usize = 60;
-
 /// Calculates the frequency of each line length across all provided files.
 fn calculate_line_length_histogram(
   files: &[FileEntry],
@@ -37,7 +35,10 @@ fn calculate_line_length_histogram(
 }
 
 /// Formats the line length histogram into a string suitable for printing.
-fn format_line_length_histogram(histogram: HashMap<usize, usize>) -> String {
+fn format_line_length_histogram(
+  histogram: HashMap<usize, usize>,
+  bars: &BarOptions,
+) -> String {
   if histogram.is_empty() {
     return "No lines found to analyze.".to_string();
   }
@@ -66,26 +67,11 @@ fn format_line_length_histogram(histogram: HashMap<usize, usize>) -> String {
     "-".repeat(9) // Length of "Histogram"
   ));
 
-  for length in sorted_lengths {
-    let count = histogram[length];
-    let bar_width = if max_count > 0 {
-      (MAX_LINE_LENGTH_HISTOGRAM_BAR as f64 * (count as f64 / max_count as f64))
-        .round() as usize
-    } else {
-      0
-    };
-
-    result += &format!(
-      "{}  {}  {}\n",
-      length
-        .to_string()
-        .pad_to_width_with_alignment(max_length_width, Alignment::Right),
-      count
-        .to_string()
-        .pad_to_width_with_alignment(max_count_width, Alignment::Right),
-      "▆".repeat(bar_width),
-    );
-  }
+  let rows: Vec<HistogramRow> = sorted_lengths
+    .into_iter()
+    .map(|length| HistogramRow::new(length.to_string(), histogram[length]))
+    .collect();
+  result += &render_histogram(&rows, bars);
 
   result
 }
@@ -95,6 +81,7 @@ pub fn process_and_output_line_length<A: Write>(
   files: Vec<FileEntry>,
   mut output_stream: A,
   json_output: bool,
+  bars: &BarOptions,
 ) -> Result<(), Box<dyn Error>> {
   let histogram = calculate_line_length_histogram(&files);
 
@@ -109,7 +96,7 @@ pub fn process_and_output_line_length<A: Write>(
     let json_string = serde_json::to_string_pretty(&histogram_vec)?;
     writeln!(output_stream, "{json_string}")?;
   } else {
-    let formatted_histogram = format_line_length_histogram(histogram);
+    let formatted_histogram = format_line_length_histogram(histogram, bars);
     writeln!(output_stream, "{formatted_histogram}")?;
   }
 
@@ -168,7 +155,8 @@ mod tests {
   #[test]
   fn test_format_line_length_histogram_empty() {
     let histogram = HashMap::new();
-    let formatted = format_line_length_histogram(histogram);
+    let formatted =
+      format_line_length_histogram(histogram, &BarOptions::default());
     assert_eq!(formatted, "No lines found to analyze.");
   }
 
@@ -176,7 +164,8 @@ mod tests {
   fn test_format_line_length_histogram_basic() {
     let histogram: HashMap<usize, usize> =
       [(5, 2), (10, 1), (15, 3)].iter().cloned().collect();
-    let formatted = format_line_length_histogram(histogram);
+    let formatted =
+      format_line_length_histogram(histogram, &BarOptions::default());
 
     // Basic check for structure, not exact bar length
     assert!(formatted.contains("Length  Count  Histogram"));
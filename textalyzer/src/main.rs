@@ -7,8 +7,13 @@ use std::process;
 use clap::Parser;
 
 use textalyzer::run;
+use textalyzer::thresholds::ThresholdExceeded;
 use textalyzer::types::{Command, Config};
 
+/// Exit code if the duplications exceed a threshold.
+/// Clap already uses 2 for invalid arguments.
+const THRESHOLD_EXCEEDED_EXIT_CODE: i32 = 3;
+
 #[derive(Parser)]
 #[command(version, about, long_about = None)]
 struct Cli {
@@ -21,6 +26,10 @@ fn main() {
 
   if let Some(command) = cli.command {
     if let Err(error) = run(Config { command }, io::stdout()) {
+      if let Some(exceeded) = error.downcast_ref::<ThresholdExceeded>() {
+        eprintln!("{exceeded}");
+        process::exit(THRESHOLD_EXCEEDED_EXIT_CODE);
+      }
       eprintln!("ERROR:\n{error}");
       process::exit(1);
     }
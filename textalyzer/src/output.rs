@@ -1,8 +1,51 @@
+use crate::thresholds::DuplicationSummary;
+use crate::types::{
+  Duplication, DuplicationItem, DuplicationLocation, SimilarDuplicationItem,
+};
 use colored::Colorize;
+use pad::{Alignment, PadStr};
 use std::error::Error;
 use std::io::Write;
 use terminal_size::{terminal_size, Width};
 
+/// Format a table of labels and right-aligned values below a title
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::output::format_table;
+///
+/// let rows = [("Words", "42".to_string()), ("Lines", "7".to_string())];
+///
+/// assert_eq!(
+///   format_table("a.txt", &rows),
+///   "a.txt\n  Words  42\n  Lines   7\n",
+/// );
+/// ```
+pub fn format_table(name: &str, rows: &[(&str, String)]) -> String {
+  let label_width = rows
+    .iter()
+    .map(|(label, _)| label.chars().count())
+    .max()
+    .unwrap_or(0);
+  let value_width = rows
+    .iter()
+    .map(|(_, value)| value.chars().count())
+    .max()
+    .unwrap_or(0);
+
+  let mut result = format!("{name}\n");
+  for (label, value) in rows {
+    result += &format!(
+      "  {}  {}\n",
+      label.pad_to_width_with_alignment(label_width, Alignment::Left),
+      value.pad_to_width_with_alignment(value_width, Alignment::Right),
+    );
+  }
+
+  result
+}
+
 /// Attempt to detect if terminal is using a light theme
 pub fn is_light_theme() -> bool {
   // Try to detect light theme by checking environment variables
@@ -30,12 +73,65 @@ pub fn is_light_theme() -> bool {
   false
 }
 
+/// Convert duplications to their JSON representation
+pub fn duplication_items(
+  duplications: Vec<Duplication>,
+) -> Vec<DuplicationItem> {
+  duplications
+    .into_iter()
+    .map(|(content, locs)| DuplicationItem {
+      content,
+      locations: locs
+        .into_iter()
+        .map(|(path, line, end_line)| DuplicationLocation {
+          path,
+          line,
+          end_line,
+        })
+        .collect(),
+    })
+    .collect()
+}
+
+/// Output how many duplications were written to a baseline file
+pub fn output_baseline_written<A: Write>(
+  count: usize,
+  path: &str,
+  mut output_stream: A,
+) -> Result<(), Box<dyn Error>> {
+  let message = format!("📝 Wrote {count} duplications to the baseline {path}");
+  writeln!(&mut output_stream, "{}", message.bold())?;
+  Ok(())
+}
+
+/// Output how many duplications were skipped
+/// because they are recorded in the baseline
+pub fn output_baseline_skipped<A: Write>(
+  count: usize,
+  mut output_stream: A,
+) -> Result<(), Box<dyn Error>> {
+  if count > 0 {
+    let message = format!("🗂️ Skipped {count} duplications of the baseline");
+    writeln!(&mut output_stream, "{}", message.dimmed())?;
+  }
+  Ok(())
+}
+
+/// Output the amount of duplicated code
+pub fn output_duplication_summary<A: Write>(
+  summary: &DuplicationSummary,
+  mut output_stream: A,
+) -> Result<(), Box<dyn Error>> {
+  writeln!(&mut output_stream, "{}", format!("📊 {summary}").bold())?;
+  Ok(())
+}
+
 /// Output duplication information to the specified stream
 ///
 /// If files_only is true, only the file paths with duplications will be shown,
 /// not the duplicated content itself.
 pub fn output_duplications<A: Write>(
-  duplications: Vec<(String, Vec<(String, u32)>)>,
+  duplications: Vec<Duplication>,
   mut output_stream: A,
   files_only: bool,
 ) -> Result<(), Box<dyn Error>> {
@@ -128,3 +224,79 @@ pub fn output_duplications<A: Write>(
 
   Ok(())
 }
+
+/// Output pairs of similar blocks to the specified stream
+///
+/// Below the content of the first block, the lines which differ
+/// between the two blocks are listed with their locations.
+/// If files_only is true, only the locations and similarities are shown.
+pub fn output_similar_duplications<A: Write>(
+  similar: Vec<SimilarDuplicationItem>,
+  mut output_stream: A,
+  files_only: bool,
+) -> Result<(), Box<dyn Error>> {
+  let is_light = is_light_theme();
+
+  if similar.is_empty() {
+    writeln!(&mut output_stream, "No similar blocks found.")?;
+    return Ok(());
+  }
+
+  let count_msg = format!("📚 Found {} similar blocks", similar.len());
+  writeln!(&mut output_stream, "{}\n", count_msg.bold())?;
+
+  let term_width = terminal_size()
+    .map(|(Width(w), _)| w as usize)
+    .unwrap_or(80);
+
+  let format_location = |path: &str, line: u32| {
+    if is_light {
+      format!("{}:{}", path.blue(), line.to_string().dimmed())
+    } else {
+      format!("{}:{}", path.dimmed(), line.to_string().yellow())
+    }
+  };
+  let list_marker = if is_light {
+    " └─ ".blue().bold()
+  } else {
+    " └─ ".bright_blue().bold()
+  };
+
+  for item in similar {
+    for location in &item.locations {
+      write!(
+        &mut output_stream,
+        "{list_marker}{}",
+        format_location(&location.path, location.line)
+      )?;
+    }
+    let similarity = format!("({:.0}% similar)", item.similarity * 100.0);
+    writeln!(&mut output_stream, "  {}\n", similarity.bold())?;
+
+    if !files_only {
+      let content = if is_light {
+        item.content.bold()
+      } else {
+        item.content.normal()
+      };
+      writeln!(&mut output_stream, "{content}")?;
+
+      if !item.differences.is_empty() {
+        writeln!(&mut output_stream, "\n{}", "Differing lines:".bold())?;
+        for difference in &item.differences {
+          writeln!(
+            &mut output_stream,
+            "  {}  {}",
+            format_location(&difference.path, difference.line),
+            difference.content
+          )?;
+        }
+      }
+
+      let separator = "-".repeat(term_width);
+      writeln!(&mut output_stream, "{separator}")?;
+    }
+  }
+
+  Ok(())
+}
@@ -0,0 +1,361 @@
+use crate::output::format_table;
+use crate::types::ReadabilityScores;
+
+/// Abbreviations that end with a period but don't end a sentence
+const ABBREVIATIONS: [&str; 15] = [
+  "mr", "mrs", "ms", "dr", "prof", "sr", "jr", "st", "vs", "etc", "e.g", "i.e",
+  "fig", "approx", "cf",
+];
+
+/// Basic counts of a text needed to calculate readability formulas.
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub struct TextCounts {
+  pub sentences: usize,
+  pub words: usize,
+  pub syllables: usize,
+  /// Number of letters in all words
+  pub letters: usize,
+  /// Number of letters and digits in all words
+  pub characters: usize,
+  /// Number of words with three or more syllables
+  pub polysyllables: usize,
+}
+
+fn is_sentence_terminator(character: char) -> bool {
+  matches!(character, '.' | '!' | '?' | '…' | '。' | '！' | '？')
+}
+
+/// Chinese and Japanese terminators, which aren't followed by a space
+fn is_full_width_terminator(character: char) -> bool {
+  matches!(character, '。' | '！' | '？')
+}
+
+fn is_closing_punctuation(character: char) -> bool {
+  matches!(
+    character,
+    '"' | '\'' | ')' | ']' | '»' | '«' | '”' | '’' | '」' | '』' | '）'
+  )
+}
+
+/// Check if the text ending with a period is an abbreviation or an initial
+fn ends_with_abbreviation(text: &str) -> bool {
+  let last_word = text
+    .rsplit(char::is_whitespace)
+    .next()
+    .unwrap_or("")
+    .trim_start_matches(|c: char| !c.is_alphanumeric())
+    .to_lowercase();
+
+  // Single letters are most likely initials like "George R. Martin"
+  last_word.chars().count() == 1 && last_word.chars().all(char::is_alphabetic)
+    || ABBREVIATIONS.contains(&last_word.as_str())
+}
+
+/// Split a text into sentences.
+///
+/// Sentences end with a terminator (., !, ?, …) followed by whitespace
+/// or with an empty line.
+/// Abbreviations like "Mr." and initials don't end a sentence.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::readability::split_sentences;
+///
+/// let sentences = split_sentences("Hello Mr. Smith! How are you? Fine.");
+///
+/// assert_eq!(sentences, vec!["Hello Mr. Smith!", "How are you?", "Fine."]);
+/// ```
+pub fn split_sentences(text: &str) -> Vec<&str> {
+  let mut sentences = Vec::new();
+  let mut start = 0;
+  let mut chars = text.char_indices().peekable();
+
+  while let Some((idx, character)) = chars.next() {
+    let mut end = None;
+
+    if is_sentence_terminator(character) {
+      // Consume repeated terminators and closing quotes, e.g. `?!"`
+      let mut last_end = idx + character.len_utf8();
+      while let Some(&(next_idx, next_char)) = chars.peek() {
+        if is_sentence_terminator(next_char)
+          || is_closing_punctuation(next_char)
+        {
+          last_end = next_idx + next_char.len_utf8();
+          chars.next();
+        } else {
+          break;
+        }
+      }
+
+      let followed_by_space = chars
+        .peek()
+        .map(|(_, next_char)| next_char.is_whitespace())
+        .unwrap_or(true);
+
+      if (followed_by_space || is_full_width_terminator(character))
+        && !(character == '.' && ends_with_abbreviation(&text[start..idx]))
+      {
+        end = Some(last_end);
+      }
+    } else if character == '\n' {
+      // An empty line always ends a sentence
+      let rest = &text[idx + 1..];
+      let next_line = rest.split('\n').next().unwrap_or("");
+      if next_line.trim().is_empty() {
+        end = Some(idx);
+      }
+    }
+
+    if let Some(end) = end {
+      let sentence = text[start..end].trim();
+      if sentence.chars().any(char::is_alphanumeric) {
+        sentences.push(sentence);
+      }
+      start = end;
+    }
+  }
+
+  let sentence = text[start..].trim();
+  if sentence.chars().any(char::is_alphanumeric) {
+    sentences.push(sentence);
+  }
+
+  sentences
+}
+
+/// Split a text into words for the readability formulas.
+/// Surrounding punctuation is removed
+/// and only words with at least one letter or digit are kept.
+pub fn split_words(text: &str) -> Vec<&str> {
+  text
+    .split_whitespace()
+    .map(|word| word.trim_matches(|c: char| !c.is_alphanumeric()))
+    .filter(|word| word.chars().any(char::is_alphanumeric))
+    .collect()
+}
+
+/// Estimate the number of syllables of an English word.
+///
+/// Counts groups of consecutive vowels
+/// and corrects for silent endings like "-e" and "-ed".
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::readability::count_syllables;
+///
+/// assert_eq!(count_syllables("cat"), 1);
+/// assert_eq!(count_syllables("table"), 2);
+/// assert_eq!(count_syllables("readability"), 5);
+/// ```
+pub fn count_syllables(word: &str) -> usize {
+  let word: Vec<char> = word
+    .to_lowercase()
+    .chars()
+    .filter(|c| c.is_alphabetic())
+    .collect();
+
+  if word.is_empty() {
+    return 0;
+  }
+
+  let is_vowel =
+    |c: char| matches!(c, 'a' | 'e' | 'i' | 'o' | 'u' | 'y' | 'ä' | 'ö' | 'ü');
+
+  let mut syllables = 0;
+  let mut previous_was_vowel = false;
+  for (idx, &character) in word.iter().enumerate() {
+    // A leading "y" is a consonant as in "yes"
+    let vowel = is_vowel(character) && !(idx == 0 && character == 'y');
+    if vowel && !previous_was_vowel {
+      syllables += 1;
+    }
+    previous_was_vowel = vowel;
+  }
+
+  let len = word.len();
+  let ends_with = |suffix: &str| {
+    let suffix: Vec<char> = suffix.chars().collect();
+    len >= suffix.len() && word[len - suffix.len()..] == suffix[..]
+  };
+  let consonant_before =
+    |offset: usize| len > offset && !is_vowel(word[len - offset - 1]);
+
+  // Silent "e" as in "make", but not "le" as in "table"
+  let silent_e = ends_with("e")
+    && consonant_before(1)
+    && !(ends_with("le") && len > 2 && consonant_before(2));
+  // Silent "ed" as in "jumped", but not "ted" or "ded" as in "wanted"
+  let silent_ed = ends_with("ed") && !ends_with("ted") && !ends_with("ded");
+
+  if silent_e || silent_ed {
+    syllables -= 1;
+  }
+
+  syllables.max(1)
+}
+
+/// Count sentences, words, syllables, and characters of a text.
+pub fn count_text(text: &str) -> TextCounts {
+  let words = split_words(text);
+  let mut counts = TextCounts {
+    sentences: split_sentences(text).len(),
+    words: words.len(),
+    ..Default::default()
+  };
+
+  for word in words {
+    let syllables = count_syllables(word);
+    counts.syllables += syllables;
+    if syllables >= 3 {
+      counts.polysyllables += 1;
+    }
+    counts.letters += word.chars().filter(|c| c.is_alphabetic()).count();
+    counts.characters += word.chars().filter(|c| c.is_alphanumeric()).count();
+  }
+
+  counts
+}
+
+/// Calculate the readability scores from the counts of a text.
+/// Returns all zeros for a text without any words.
+pub fn calculate_readability(counts: &TextCounts) -> ReadabilityScores {
+  if counts.words == 0 {
+    return ReadabilityScores::default();
+  }
+
+  let words = counts.words as f64;
+  let sentences = counts.sentences.max(1) as f64;
+  let words_per_sentence = words / sentences;
+  let syllables_per_word = counts.syllables as f64 / words;
+  let polysyllables = counts.polysyllables as f64;
+
+  ReadabilityScores {
+    flesch_reading_ease: 206.835
+      - 1.015 * words_per_sentence
+      - 84.6 * syllables_per_word,
+    flesch_kincaid_grade: 0.39 * words_per_sentence + 11.8 * syllables_per_word
+      - 15.59,
+    gunning_fog: 0.4 * (words_per_sentence + 100.0 * polysyllables / words),
+    smog: 1.043 * (polysyllables * 30.0 / sentences).sqrt() + 3.1291,
+    coleman_liau: 0.0588 * (counts.letters as f64 / words * 100.0)
+      - 0.296 * (sentences / words * 100.0)
+      - 15.8,
+    automated_readability_index: 4.71 * (counts.characters as f64 / words)
+      + 0.5 * words_per_sentence
+      - 21.43,
+  }
+}
+
+/// Format the readability scores of a file into a string.
+pub fn format_readability(
+  name: &str,
+  counts: &TextCounts,
+  scores: &ReadabilityScores,
+) -> String {
+  let rows = [
+    ("Sentences", counts.sentences.to_string()),
+    ("Words", counts.words.to_string()),
+    ("Syllables", counts.syllables.to_string()),
+    (
+      "Flesch Reading Ease",
+      format!("{:.2}", scores.flesch_reading_ease),
+    ),
+    (
+      "Flesch-Kincaid Grade",
+      format!("{:.2}", scores.flesch_kincaid_grade),
+    ),
+    ("Gunning Fog", format!("{:.2}", scores.gunning_fog)),
+    ("SMOG", format!("{:.2}", scores.smog)),
+    ("Coleman-Liau", format!("{:.2}", scores.coleman_liau)),
+    (
+      "Automated Readability Index",
+      format!("{:.2}", scores.automated_readability_index),
+    ),
+  ];
+
+  format_table(name, &rows)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_split_sentences() {
+    let text = "It was a bright cold day in April. The clocks were striking \
+      thirteen!\nDr. Smith said: \"Really?\" Yes...\n\nNew paragraph";
+    let sentences = split_sentences(text);
+
+    assert_eq!(
+      sentences,
+      vec![
+        "It was a bright cold day in April.",
+        "The clocks were striking thirteen!",
+        "Dr. Smith said: \"Really?\"",
+        "Yes...",
+        "New paragraph",
+      ]
+    );
+  }
+
+  #[test]
+  fn test_split_sentences_without_terminator() {
+    let text = "Herr von Ribbeck auf Ribbeck im Havelland,\n\
+      Ein Birnbaum in seinem Garten stand,\n\n\
+      So ging es viel Jahre";
+
+    assert_eq!(split_sentences(text).len(), 2);
+  }
+
+  #[test]
+  fn test_split_sentences_cjk() {
+    assert_eq!(
+      split_sentences("今天很好。明天也好！「本当？」はい"),
+      vec!["今天很好。", "明天也好！", "「本当？」", "はい"]
+    );
+  }
+
+  #[test]
+  fn test_count_syllables() {
+    let cases = [
+      ("the", 1),
+      ("make", 1),
+      ("jumped", 1),
+      ("wanted", 2),
+      ("beautiful", 3),
+      ("yes", 1),
+      ("simple", 2),
+      ("", 0),
+    ];
+
+    for (word, expected) in cases {
+      assert_eq!(count_syllables(word), expected, "Word: {word}");
+    }
+  }
+
+  #[test]
+  fn test_calculate_readability() {
+    let counts = count_text("The cat sat on the mat. The dog ate the food.");
+
+    assert_eq!(counts.sentences, 2);
+    assert_eq!(counts.words, 11);
+    assert_eq!(counts.syllables, 11);
+    assert_eq!(counts.polysyllables, 0);
+
+    let scores = calculate_readability(&counts);
+
+    // 206.835 - 1.015 * 5.5 - 84.6 * 1.0
+    assert!((scores.flesch_reading_ease - 116.6525).abs() < 1e-9);
+    // 0.39 * 5.5 + 11.8 * 1.0 - 15.59
+    assert!((scores.flesch_kincaid_grade - (-1.645)).abs() < 1e-9);
+    assert!((scores.gunning_fog - 2.2).abs() < 1e-9);
+  }
+
+  #[test]
+  fn test_calculate_readability_empty() {
+    let scores = calculate_readability(&count_text(""));
+    assert_eq!(scores, ReadabilityScores::default());
+  }
+}
@@ -0,0 +1,221 @@
+use crate::language::{parse_word_list, Language};
+use std::collections::HashSet;
+use std::sync::OnceLock;
+use unicode_segmentation::UnicodeSegmentation;
+
+/// Scripts which are written without spaces between words
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Script {
+  /// Chinese characters, Hiragana, and Katakana
+  Cjk,
+  Thai,
+}
+
+/// Get the script of a character if it needs dictionary-based segmentation.
+pub fn script_of(character: char) -> Option<Script> {
+  match character {
+    '\u{3005}' // Ideographic iteration mark
+    | '\u{3040}'..='\u{309F}' // Hiragana
+    | '\u{30A0}'..='\u{30FF}' // Katakana
+    | '\u{31F0}'..='\u{31FF}' // Katakana phonetic extensions
+    | '\u{3400}'..='\u{4DBF}' // CJK unified ideographs extension A
+    | '\u{4E00}'..='\u{9FFF}' // CJK unified ideographs
+    | '\u{F900}'..='\u{FAFF}' // CJK compatibility ideographs
+    | '\u{FF66}'..='\u{FF9F}' // Halfwidth Katakana
+    | '\u{20000}'..='\u{2A6DF}' => Some(Script::Cjk),
+    // Thai consonants, vowels, and tone marks
+    // without digits and punctuation like "ๆ" or "ฯ"
+    '\u{0E01}'..='\u{0E2E}'
+    | '\u{0E30}'..='\u{0E3A}'
+    | '\u{0E40}'..='\u{0E45}'
+    | '\u{0E47}'..='\u{0E4E}' => Some(Script::Thai),
+    _ => None,
+  }
+}
+
+fn is_kana(character: char) -> bool {
+  matches!(
+    character,
+    '\u{3040}'..='\u{30FF}' | '\u{31F0}'..='\u{31FF}' | '\u{FF66}'..='\u{FF9F}'
+  )
+}
+
+/// Detect the language of CJK text.
+/// Japanese is written with Hiragana or Katakana, Chinese without them.
+pub fn detect_cjk_language(text: &str) -> Language {
+  if text.chars().any(is_kana) {
+    Language::Japanese
+  } else {
+    Language::Chinese
+  }
+}
+
+/// A word list for dictionary-based segmentation
+#[derive(Debug)]
+pub struct Dictionary {
+  words: HashSet<String>,
+  /// Length of the longest word in grapheme clusters
+  max_word_length: usize,
+  /// Join unknown characters up to the next known word,
+  /// so that unknown Thai syllables aren't split apart.
+  /// Chinese and Japanese characters are words on their own instead.
+  join_unknown: bool,
+}
+
+impl Dictionary {
+  /// Create a dictionary from a word list with one word per line.
+  pub fn from_word_list(content: &str) -> Dictionary {
+    let words = parse_word_list(content);
+    let max_word_length = words
+      .iter()
+      .map(|word| word.graphemes(true).count())
+      .max()
+      .unwrap_or(1);
+
+    Dictionary {
+      words,
+      max_word_length,
+      join_unknown: false,
+    }
+  }
+
+  /// Get the bundled dictionary of a language, if it has one.
+  pub fn for_language(language: Language) -> Option<&'static Dictionary> {
+    static CHINESE: OnceLock<Dictionary> = OnceLock::new();
+    static JAPANESE: OnceLock<Dictionary> = OnceLock::new();
+    static THAI: OnceLock<Dictionary> = OnceLock::new();
+
+    let cell = match language {
+      Language::Chinese => &CHINESE,
+      Language::Japanese => &JAPANESE,
+      Language::Thai => &THAI,
+      _ => return None,
+    };
+
+    language.dictionary().map(|content| {
+      cell.get_or_init(|| Dictionary {
+        join_unknown: language == Language::Thai,
+        ..Dictionary::from_word_list(content)
+      })
+    })
+  }
+
+  pub fn contains(&self, word: &str) -> bool {
+    self.words.contains(word)
+  }
+}
+
+/// Split a text without spaces into words with their byte offsets.
+///
+/// Uses forward maximum matching: At each position the longest word
+/// of the dictionary is taken. Unknown Chinese and Japanese characters
+/// become words of their own, unknown Thai text is kept together
+/// up to the next known word.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::language::Language;
+/// use textalyzer::segmenter::{segment, Dictionary};
+///
+/// let dictionary = Dictionary::for_language(Language::Chinese).unwrap();
+///
+/// assert_eq!(
+///   segment("我们喜欢学习中文", dictionary),
+///   vec![(0, "我们"), (6, "喜欢"), (12, "学习"), (18, "中文")],
+/// );
+/// ```
+pub fn segment<'a>(
+  text: &'a str,
+  dictionary: &Dictionary,
+) -> Vec<(usize, &'a str)> {
+  let boundaries: Vec<usize> = text
+    .grapheme_indices(true)
+    .map(|(idx, _)| idx)
+    .chain(std::iter::once(text.len()))
+    .collect();
+  let last = boundaries.len() - 1;
+  let mut words = Vec::new();
+  let mut idx = 0;
+
+  // End of the longest known word starting at the grapheme `idx`
+  let known_end = |idx: usize| {
+    let longest = (idx + dictionary.max_word_length).min(last);
+    (idx + 1..=longest)
+      .rev()
+      .find(|&end| dictionary.contains(&text[boundaries[idx]..boundaries[end]]))
+  };
+
+  while idx < last {
+    let start = boundaries[idx];
+    let end = known_end(idx).unwrap_or_else(|| {
+      let mut end = idx + 1;
+      while dictionary.join_unknown && end < last && known_end(end).is_none() {
+        end += 1;
+      }
+      end
+    });
+
+    words.push((start, &text[start..boundaries[end]]));
+    idx = end;
+  }
+
+  words
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn segment_words(text: &str, language: Language) -> Vec<&str> {
+    let dictionary = Dictionary::for_language(language).unwrap();
+    segment(text, dictionary)
+      .into_iter()
+      .map(|(_, word)| word)
+      .collect()
+  }
+
+  #[test]
+  fn test_script_of() {
+    assert_eq!(script_of('中'), Some(Script::Cjk));
+    assert_eq!(script_of('の'), Some(Script::Cjk));
+    assert_eq!(script_of('ข'), Some(Script::Thai));
+    assert_eq!(script_of('ไ'), Some(Script::Thai));
+    assert_eq!(script_of('๕'), None);
+    assert_eq!(script_of('ๆ'), None);
+    assert_eq!(script_of('a'), None);
+    assert_eq!(script_of('。'), None);
+  }
+
+  #[test]
+  fn test_detect_cjk_language() {
+    assert_eq!(detect_cjk_language("我们学习中文"), Language::Chinese);
+    assert_eq!(detect_cjk_language("私は学生です"), Language::Japanese);
+  }
+
+  #[test]
+  fn test_segment_japanese() {
+    assert_eq!(
+      segment_words("私は学生です", Language::Japanese),
+      vec!["私", "は", "学生", "です"]
+    );
+  }
+
+  #[test]
+  fn test_segment_thai() {
+    assert_eq!(
+      segment_words("ผมชอบกินข้าวไหม", Language::Thai),
+      vec!["ผม", "ชอบ", "กิน", "ข้าว", "ไหม"]
+    );
+    // Unknown syllables stay whole with their vowel signs and tone marks
+    assert_eq!(
+      segment_words("ผมชอบกุ้งมาก", Language::Thai),
+      vec!["ผม", "ชอบ", "กุ้ง", "มาก"]
+    );
+  }
+
+  #[test]
+  fn test_segment_empty() {
+    assert!(segment_words("", Language::Chinese).is_empty());
+  }
+}
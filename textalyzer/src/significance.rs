@@ -0,0 +1,54 @@
+/// Dunning's log-likelihood ratio (G²) of a 2×2 contingency table.
+/// `k11` and `k12` are the cells of the first row,
+/// `k21` and `k22` the cells of the second row.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::significance::log_likelihood;
+///
+/// // Independent rows and columns
+/// assert!(log_likelihood(10.0, 10.0, 10.0, 10.0).abs() < 1e-9);
+/// assert!(log_likelihood(10.0, 0.0, 0.0, 30.0) > 20.0);
+/// ```
+pub fn log_likelihood(k11: f64, k12: f64, k21: f64, k22: f64) -> f64 {
+  let total = k11 + k12 + k21 + k22;
+  let cells = [
+    (k11, (k11 + k12) * (k11 + k21)),
+    (k12, (k11 + k12) * (k12 + k22)),
+    (k21, (k21 + k22) * (k11 + k21)),
+    (k22, (k21 + k22) * (k12 + k22)),
+  ];
+
+  2.0
+    * cells
+      .iter()
+      .filter(|(observed, _)| *observed > 0.0)
+      .map(|(observed, marginals)| {
+        let expected = marginals / total;
+        observed * (observed / expected).ln()
+      })
+      .sum::<f64>()
+}
+
+/// Pearson's chi-squared statistic of a 2×2 contingency table
+/// (without Yates' continuity correction).
+/// Tables with an empty row or column have a score of 0.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::significance::chi_squared;
+///
+/// assert_eq!(chi_squared(10.0, 10.0, 10.0, 10.0), 0.0);
+/// assert_eq!(chi_squared(10.0, 0.0, 0.0, 10.0), 20.0);
+/// ```
+pub fn chi_squared(k11: f64, k12: f64, k21: f64, k22: f64) -> f64 {
+  let marginals = (k11 + k12) * (k21 + k22) * (k11 + k21) * (k12 + k22);
+  if marginals == 0.0 {
+    return 0.0;
+  }
+
+  let total = k11 + k12 + k21 + k22;
+  total * (k11 * k22 - k12 * k21).powi(2) / marginals
+}
@@ -0,0 +1,962 @@
+use crate::duplication::remove_common_indent;
+use crate::types::{
+  DuplicationLocation, FileEntry, LineDifference, SimilarDuplicationItem,
+};
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::{HashMap, HashSet};
+use std::hash::{Hash, Hasher};
+
+/// Number of consecutive tokens which form one shingle
+const SHINGLE_SIZE: usize = 2;
+/// Number of LSH bands
+const BANDS: usize = 21;
+/// Number of MinHash values per LSH band
+const ROWS: usize = 3;
+/// LSH buckets with more windows than this are only compared against
+/// their first window to avoid a quadratic number of comparisons
+/// for boilerplate which occurs in many places.
+const MAX_BUCKET_SIZE: usize = 100;
+/// Maximum number of non-empty lines of a block.
+/// Longer similar sections are reported as several blocks.
+const MAX_BLOCK_LINES: usize = 500;
+
+type Signature = [u64; BANDS * ROWS];
+
+/// Non-empty line of a file
+struct Line {
+  number: u32,
+  /// Hashes of the tokens of the line
+  tokens: Vec<u64>,
+}
+
+struct FileLines<'a> {
+  name: &'a str,
+  all_lines: Vec<&'a str>,
+  lines: Vec<Line>,
+}
+
+/// Sequence of `min_lines` non-empty lines starting at the given line
+#[derive(Clone, Copy)]
+struct Window {
+  file: usize,
+  start: usize,
+}
+
+/// Split a line of code into words (including identifiers and numbers)
+/// and single punctuation characters.
+fn code_tokens(line: &str) -> Vec<&str> {
+  let mut tokens = Vec::new();
+  let mut word_start = None;
+
+  for (idx, character) in line.char_indices() {
+    if character.is_alphanumeric() || character == '_' {
+      word_start.get_or_insert(idx);
+      continue;
+    }
+    if let Some(start) = word_start.take() {
+      tokens.push(&line[start..idx]);
+    }
+    if !character.is_whitespace() {
+      tokens.push(&line[idx..idx + character.len_utf8()]);
+    }
+  }
+  if let Some(start) = word_start {
+    tokens.push(&line[start..]);
+  }
+
+  tokens
+}
+
+/// Mix the bits of a hash (finalizer of SplitMix64)
+fn mix(mut value: u64) -> u64 {
+  value = (value ^ (value >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+  value = (value ^ (value >> 27)).wrapping_mul(0x94d049bb133111eb);
+  value ^ (value >> 31)
+}
+
+fn hash<T: Hash + ?Sized>(value: &T) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  value.hash(&mut hasher);
+  hasher.finish()
+}
+
+fn shingles(tokens: &[u64]) -> HashSet<u64> {
+  if tokens.is_empty() {
+    return HashSet::new();
+  }
+  tokens
+    .windows(SHINGLE_SIZE.min(tokens.len()))
+    .map(hash)
+    .collect()
+}
+
+/// MinHash signature of a set of shingles.
+/// The share of equal values of two signatures
+/// estimates the Jaccard similarity of the two sets.
+fn minhash(shingles: &HashSet<u64>) -> Signature {
+  let mut signature = [u64::MAX; BANDS * ROWS];
+  for (idx, value) in signature.iter_mut().enumerate() {
+    let seed = mix(idx as u64 + 1);
+    for shingle in shingles {
+      *value = (*value).min(mix(shingle ^ seed));
+    }
+  }
+  signature
+}
+
+/// Length of the common prefix and the common suffix of two sequences,
+/// which don't overlap
+fn common_ends<T: PartialEq>(a: &[T], b: &[T]) -> (usize, usize) {
+  let prefix = a.iter().zip(b).take_while(|(a, b)| a == b).count();
+  let suffix = a[prefix..]
+    .iter()
+    .rev()
+    .zip(b[prefix..].iter().rev())
+    .take_while(|(a, b)| a == b)
+    .count();
+  (prefix, suffix)
+}
+
+/// Length of the longest common subsequence of two sequences
+/// if they differ by at most `max_edits` inserted or deleted items.
+///
+/// Uses Myers' diff algorithm, which takes O((n + m) · d) time
+/// for d edits and linear memory, so nearly identical sequences
+/// are compared in linear time.
+fn lcs_length<T: PartialEq>(
+  a: &[T],
+  b: &[T],
+  max_edits: usize,
+) -> Option<usize> {
+  let (prefix, suffix) = common_ends(a, b);
+  let (a, b) = (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+  let (n, m) = (a.len() as isize, b.len() as isize);
+  let max_edits = max_edits.min(a.len() + b.len()) as isize;
+
+  // Furthest x position on each diagonal k = x - y
+  let offset = max_edits + 1;
+  let mut furthest = vec![0isize; 2 * max_edits as usize + 3];
+  for edits in 0..=max_edits {
+    for diagonal in (-edits..=edits).step_by(2) {
+      let idx = (diagonal + offset) as usize;
+      let mut x = if diagonal == -edits
+        || (diagonal != edits && furthest[idx - 1] < furthest[idx + 1])
+      {
+        furthest[idx + 1]
+      } else {
+        furthest[idx - 1] + 1
+      };
+      let mut y = x - diagonal;
+      while x < n && y < m && a[x as usize] == b[y as usize] {
+        x += 1;
+        y += 1;
+      }
+      furthest[idx] = x;
+      if x >= n && y >= m {
+        let common = (a.len() + b.len() - edits as usize) / 2;
+        return Some(prefix + suffix + common);
+      }
+    }
+  }
+
+  None
+}
+
+/// Mark the items of both sequences which are part of
+/// a longest common subsequence.
+/// Only the part between the common prefix and suffix
+/// needs a dynamic programming table.
+fn lcs_matches<T: PartialEq>(a: &[T], b: &[T]) -> (Vec<bool>, Vec<bool>) {
+  let (prefix, suffix) = common_ends(a, b);
+  let mut matched_a = vec![false; a.len()];
+  let mut matched_b = vec![false; b.len()];
+  for idx in (0..prefix).chain(a.len() - suffix..a.len()) {
+    matched_a[idx] = true;
+  }
+  for idx in (0..prefix).chain(b.len() - suffix..b.len()) {
+    matched_b[idx] = true;
+  }
+
+  let (middle_a, middle_b) =
+    (&a[prefix..a.len() - suffix], &b[prefix..b.len() - suffix]);
+  let width = middle_b.len() + 1;
+  let mut table = vec![0u32; (middle_a.len() + 1) * width];
+  for idx_a in (0..middle_a.len()).rev() {
+    for idx_b in (0..middle_b.len()).rev() {
+      table[idx_a * width + idx_b] = if middle_a[idx_a] == middle_b[idx_b] {
+        table[(idx_a + 1) * width + idx_b + 1] + 1
+      } else {
+        table[(idx_a + 1) * width + idx_b].max(table[idx_a * width + idx_b + 1])
+      };
+    }
+  }
+
+  let (mut idx_a, mut idx_b) = (0, 0);
+  while idx_a < middle_a.len() && idx_b < middle_b.len() {
+    if middle_a[idx_a] == middle_b[idx_b] {
+      matched_a[prefix + idx_a] = true;
+      matched_b[prefix + idx_b] = true;
+      idx_a += 1;
+      idx_b += 1;
+    } else if table[(idx_a + 1) * width + idx_b]
+      >= table[idx_a * width + idx_b + 1]
+    {
+      idx_a += 1;
+    } else {
+      idx_b += 1;
+    }
+  }
+
+  (matched_a, matched_b)
+}
+
+/// Similarity of two token sequences between 0 and 1:
+/// Twice the number of tokens of their longest common subsequence
+/// divided by the total number of tokens.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::similarity::sequence_similarity;
+///
+/// assert_eq!(sequence_similarity(&["a", "b", "c"], &["a", "x", "c"]), 2.0 / 3.0);
+/// ```
+pub fn sequence_similarity<T: PartialEq>(a: &[T], b: &[T]) -> f64 {
+  if a.is_empty() && b.is_empty() {
+    return 1.0;
+  }
+  let common = lcs_length(a, b, a.len() + b.len()).unwrap_or(0);
+  2.0 * common as f64 / (a.len() + b.len()) as f64
+}
+
+/// Whether the `sequence_similarity` of two token sequences
+/// is at least `threshold`.
+/// The search for a common subsequence stops as soon as there are
+/// too many differences, which makes dissimilar sequences cheap.
+fn is_similar<T: PartialEq>(a: &[T], b: &[T], threshold: f64) -> bool {
+  if a.is_empty() && b.is_empty() {
+    return true;
+  }
+  let total = a.len() + b.len();
+  let max_edits = ((1.0 - threshold) * total as f64).floor().max(0.0);
+  lcs_length(a, b, max_edits as usize)
+    .is_some_and(|common| 2.0 * common as f64 / total as f64 >= threshold)
+}
+
+/// Upper bound of the `sequence_similarity` of two token sequences,
+/// which is cheaper to calculate as it ignores the order of the tokens
+fn similarity_bound(a: &[u64], b: &[u64]) -> f64 {
+  if a.is_empty() && b.is_empty() {
+    return 1.0;
+  }
+  let (mut sorted_a, mut sorted_b) = (a.to_vec(), b.to_vec());
+  sorted_a.sort_unstable();
+  sorted_b.sort_unstable();
+
+  let (mut idx_a, mut idx_b, mut common) = (0, 0, 0);
+  while idx_a < sorted_a.len() && idx_b < sorted_b.len() {
+    match sorted_a[idx_a].cmp(&sorted_b[idx_b]) {
+      std::cmp::Ordering::Less => idx_a += 1,
+      std::cmp::Ordering::Greater => idx_b += 1,
+      std::cmp::Ordering::Equal => {
+        common += 1;
+        idx_a += 1;
+        idx_b += 1;
+      }
+    }
+  }
+
+  2.0 * common as f64 / (a.len() + b.len()) as f64
+}
+
+impl FileLines<'_> {
+  fn tokens(&self, start: usize, end: usize) -> Vec<u64> {
+    self.lines[start..end]
+      .iter()
+      .flat_map(|line| line.tokens.iter().copied())
+      .collect()
+  }
+}
+
+/// Contiguous range of non-empty lines of a file
+#[derive(Clone, Copy)]
+struct Block {
+  file: usize,
+  start: usize,
+  end: usize,
+}
+
+impl Block {
+  fn len(&self) -> usize {
+    self.end - self.start
+  }
+
+  /// Lines from `start` to `end` relative to the start of the block
+  fn part(&self, start: usize, end: usize) -> Block {
+    Block {
+      start: self.start + start,
+      end: self.start + end,
+      ..*self
+    }
+  }
+
+  fn overlaps(&self, other: &Block) -> bool {
+    self.file == other.file && self.start < other.end && other.start < self.end
+  }
+}
+
+struct Detector<'a> {
+  files: Vec<FileLines<'a>>,
+  windows: Vec<Window>,
+  window_size: usize,
+  threshold: f64,
+}
+
+impl Detector<'_> {
+  fn block(&self, window: usize, window_count: usize) -> Block {
+    let Window { file, start } = self.windows[window];
+    Block {
+      file,
+      start,
+      end: start + window_count - 1 + self.window_size,
+    }
+  }
+
+  fn tokens(&self, block: &Block) -> Vec<u64> {
+    self.files[block.file].tokens(block.start, block.end)
+  }
+
+  fn similarity(&self, a: &Block, b: &Block) -> f64 {
+    sequence_similarity(&self.tokens(a), &self.tokens(b))
+  }
+
+  /// Whether two blocks are similar enough and don't overlap
+  fn is_block_match(&self, a: &Block, b: &Block) -> bool {
+    if a.overlaps(b) {
+      return false;
+    }
+    let (tokens_a, tokens_b) = (self.tokens(a), self.tokens(b));
+    similarity_bound(&tokens_a, &tokens_b) >= self.threshold
+      && is_similar(&tokens_a, &tokens_b, self.threshold)
+  }
+
+  /// Whether two windows are similar enough and don't overlap
+  fn is_match(&self, a: usize, b: usize) -> bool {
+    self.is_block_match(&self.block(a, 1), &self.block(b, 1))
+  }
+
+  /// The window after the given one in the same file
+  fn next_window(&self, window: usize) -> Option<usize> {
+    let next = window + 1;
+    (next < self.windows.len()
+      && self.windows[next].file == self.windows[window].file)
+      .then_some(next)
+  }
+
+  /// The window before the given one in the same file
+  fn previous_window(&self, window: usize) -> Option<usize> {
+    let previous = window.checked_sub(1)?;
+    (self.windows[previous].file == self.windows[window].file)
+      .then_some(previous)
+  }
+
+  /// Pairs of windows which share at least one LSH bucket
+  fn candidates(&self) -> Vec<(usize, usize)> {
+    let signatures: Vec<Option<Signature>> = (0..self.windows.len())
+      .into_par_iter()
+      .map(|window| {
+        let shingles = shingles(&self.tokens(&self.block(window, 1)));
+        (!shingles.is_empty()).then(|| minhash(&shingles))
+      })
+      .collect();
+
+    let mut buckets: HashMap<(usize, u64), Vec<usize>> = HashMap::new();
+    for (window, signature) in signatures.iter().enumerate() {
+      let Some(signature) = signature else {
+        continue;
+      };
+      for (band, rows) in signature.chunks(ROWS).enumerate() {
+        buckets.entry((band, hash(rows))).or_default().push(window);
+      }
+    }
+
+    let mut candidates = Vec::new();
+    for windows in buckets.values() {
+      if windows.len() > MAX_BUCKET_SIZE {
+        candidates.extend(windows[1..].iter().map(|&b| (windows[0], b)));
+        continue;
+      }
+      for (idx, &a) in windows.iter().enumerate() {
+        candidates.extend(windows[idx + 1..].iter().map(|&b| (a, b)));
+      }
+    }
+    candidates.sort_unstable();
+    candidates.dedup();
+    candidates
+  }
+
+  /// Whether the blocks of two runs of windows overlap,
+  /// e.g. for repetitive code within one file
+  fn run_overlaps(
+    &self,
+    first_a: usize,
+    first_b: usize,
+    last_a: usize,
+  ) -> bool {
+    let length = last_a - first_a + 1;
+    self
+      .block(first_a, length)
+      .overlaps(&self.block(first_b, length))
+  }
+
+  /// Whether a run from the first to the last window
+  /// would be longer than `MAX_BLOCK_LINES`
+  fn is_run_too_long(&self, first: usize, last: usize) -> bool {
+    last - first + self.window_size > MAX_BLOCK_LINES
+  }
+
+  /// Extend matching window pairs along both files
+  /// to runs of consecutive matching windows.
+  /// Returns the first window pair and the number of windows of each run.
+  fn runs(&self) -> Vec<(usize, usize, usize)> {
+    let matches: Vec<(usize, usize)> = self
+      .candidates()
+      .into_par_iter()
+      .filter(|&(a, b)| self.is_match(a, b))
+      .collect();
+
+    let mut covered: HashSet<(usize, usize)> = HashSet::new();
+    let mut runs = Vec::new();
+
+    for (a, b) in matches {
+      if covered.contains(&(a, b)) {
+        continue;
+      }
+
+      let (mut first_a, mut first_b) = (a, b);
+      while let (Some(previous_a), Some(previous_b)) =
+        (self.previous_window(first_a), self.previous_window(first_b))
+      {
+        if covered.contains(&(previous_a, previous_b))
+          || self.is_run_too_long(previous_a, a)
+          || self.run_overlaps(previous_a, previous_b, a)
+          || !self.is_match(previous_a, previous_b)
+        {
+          break;
+        }
+        (first_a, first_b) = (previous_a, previous_b);
+      }
+
+      let (mut last_a, mut last_b) = (a, b);
+      while let (Some(next_a), Some(next_b)) =
+        (self.next_window(last_a), self.next_window(last_b))
+      {
+        if self.is_run_too_long(first_a, next_a)
+          || self.run_overlaps(first_a, first_b, next_a)
+          || !self.is_match(next_a, next_b)
+        {
+          break;
+        }
+        (last_a, last_b) = (next_a, next_b);
+      }
+
+      // The windows overlapping the end of a run which reached the maximum
+      // length can't start the next run
+      let length = last_a - first_a + 1;
+      let covered_length = if self.is_run_too_long(first_a, last_a + 1) {
+        length + self.window_size - 1
+      } else {
+        length
+      };
+      for offset in 0..covered_length {
+        covered.insert((first_a + offset, first_b + offset));
+      }
+      runs.push((first_a, first_b, length));
+    }
+
+    runs
+  }
+
+  fn lines_equal(
+    &self,
+    a: &Block,
+    idx_a: usize,
+    b: &Block,
+    idx_b: usize,
+  ) -> bool {
+    let (lines_a, lines_b) =
+      (&self.files[a.file].lines, &self.files[b.file].lines);
+    idx_a < lines_a.len()
+      && idx_b < lines_b.len()
+      && lines_a[idx_a].tokens == lines_b[idx_b].tokens
+  }
+
+  /// Whether two extended blocks are still short enough and don't overlap
+  fn can_extend(&self, a: &Block, b: &Block) -> bool {
+    a.len().max(b.len()) <= MAX_BLOCK_LINES && !a.overlaps(b)
+  }
+
+  /// Extend a pair of blocks over up to `window_size` differing lines
+  /// as long as the extension ends with an identical line
+  /// and the blocks stay similar enough.
+  /// This includes e.g. closing brackets after a changed line,
+  /// which the windows of the changed line don't match.
+  /// Identical lines are added without comparing the blocks again,
+  /// as they can't lower the similarity.
+  fn extend(&self, mut a: Block, mut b: Block) -> (Block, Block) {
+    'forward: loop {
+      for gap in 0..self.window_size {
+        if !self.lines_equal(&a, a.end + gap, &b, b.end + gap) {
+          continue;
+        }
+        let extended_a = Block {
+          end: a.end + gap + 1,
+          ..a
+        };
+        let extended_b = Block {
+          end: b.end + gap + 1,
+          ..b
+        };
+        if !self.can_extend(&extended_a, &extended_b) {
+          break 'forward;
+        }
+        if gap == 0 || self.is_block_match(&extended_a, &extended_b) {
+          (a, b) = (extended_a, extended_b);
+          continue 'forward;
+        }
+      }
+      break;
+    }
+
+    'backward: loop {
+      for gap in 0..self.window_size.min(a.start).min(b.start) {
+        let (start_a, start_b) = (a.start - gap - 1, b.start - gap - 1);
+        if !self.lines_equal(&a, start_a, &b, start_b) {
+          continue;
+        }
+        let extended_a = Block {
+          start: start_a,
+          ..a
+        };
+        let extended_b = Block {
+          start: start_b,
+          ..b
+        };
+        if !self.can_extend(&extended_a, &extended_b) {
+          break 'backward;
+        }
+        if gap == 0 || self.is_block_match(&extended_a, &extended_b) {
+          (a, b) = (extended_a, extended_b);
+          continue 'backward;
+        }
+      }
+      break;
+    }
+
+    (a, b)
+  }
+
+  /// Longest part of a pair of blocks in which the lines of at least
+  /// one of them aren't part of the `kept` blocks.
+  /// Both blocks have the same length, as they are extended together.
+  fn uncovered_part(
+    &self,
+    a: &Block,
+    b: &Block,
+    kept: &[Block],
+  ) -> (Block, Block) {
+    let is_covered = |block: &Block, offset: usize| {
+      let line = block.start + offset;
+      kept.iter().any(|other| {
+        other.file == block.file && other.start <= line && line < other.end
+      })
+    };
+    let mut longest = (0, 0);
+    let mut start = 0;
+    for offset in 0..=a.len() {
+      if offset == a.len() || (is_covered(a, offset) && is_covered(b, offset)) {
+        if offset - start > longest.1 - longest.0 {
+          longest = (start, offset);
+        }
+        start = offset + 1;
+      }
+    }
+    (a.part(longest.0, longest.1), b.part(longest.0, longest.1))
+  }
+
+  fn location(&self, block: &Block) -> DuplicationLocation {
+    let file = &self.files[block.file];
+    DuplicationLocation {
+      path: file.name.to_string(),
+      line: file.lines[block.start].number,
+      end_line: file.lines[block.end - 1].number,
+    }
+  }
+
+  fn content(&self, block: &Block) -> String {
+    let file = &self.files[block.file];
+    let first = file.lines[block.start].number as usize - 1;
+    let last = file.lines[block.end - 1].number as usize;
+    remove_common_indent(&file.all_lines[first..last])
+  }
+
+  /// Lines which are not part of the longest common subsequence
+  /// of the lines of both blocks
+  fn differences(&self, a: &Block, b: &Block) -> Vec<LineDifference> {
+    let trimmed_lines = |block: &Block| -> Vec<(&str, u32)> {
+      let file = &self.files[block.file];
+      file.lines[block.start..block.end]
+        .iter()
+        .map(|line| {
+          (file.all_lines[line.number as usize - 1].trim(), line.number)
+        })
+        .collect()
+    };
+    let (lines_a, lines_b) = (trimmed_lines(a), trimmed_lines(b));
+    let texts_a: Vec<&str> = lines_a.iter().map(|(text, _)| *text).collect();
+    let texts_b: Vec<&str> = lines_b.iter().map(|(text, _)| *text).collect();
+    let (matched_a, matched_b) = lcs_matches(&texts_a, &texts_b);
+
+    let unmatched = |block: &Block, lines: &[(&str, u32)], matched: &[bool]| {
+      let path = self.files[block.file].name;
+      lines
+        .iter()
+        .zip(matched)
+        .filter(|(_, matched)| !**matched)
+        .map(|((content, line), _)| LineDifference {
+          path: path.to_string(),
+          line: *line,
+          content: content.to_string(),
+        })
+        .collect::<Vec<_>>()
+    };
+
+    let mut differences = unmatched(a, &lines_a, &matched_a);
+    differences.extend(unmatched(b, &lines_b, &matched_b));
+    differences
+  }
+}
+
+/// Find pairs of nearly identical blocks of at least `min_lines`
+/// non-empty lines, across files or within the same file.
+///
+/// Every sequence of `min_lines` non-empty lines is split into tokens
+/// and indexed with MinHash signatures of its token shingles.
+/// Sequences which share a locality-sensitive hashing bucket
+/// are compared token by token and kept if their similarity
+/// (see `sequence_similarity`) is at least `threshold`.
+/// Consecutive matches are merged into larger blocks
+/// and extended over short gaps.
+/// The largest pairs come first, and pairs whose blocks both overlap
+/// blocks of previously reported pairs are reduced to the part
+/// outside of them or left out.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::similarity::find_similar_blocks;
+/// use textalyzer::types::{FileEntry, MappedContent};
+///
+/// let file = |name: &str, content: &str| FileEntry {
+///   name: name.to_string(),
+///   content: MappedContent::String(content.to_string()),
+/// };
+/// let files = vec![
+///   file("a.rs", "let total = price * count;\nprintln!(\"{}\", total);\n"),
+///   file("b.rs", "let sum = price * count;\nprintln!(\"{}\", sum);\n"),
+/// ];
+/// let similar = find_similar_blocks(&files, 2, 0.8);
+///
+/// assert_eq!(similar.len(), 1);
+/// assert_eq!(similar[0].differences.len(), 4);
+/// ```
+pub fn find_similar_blocks(
+  files: &[FileEntry],
+  min_lines: usize,
+  threshold: f64,
+) -> Vec<SimilarDuplicationItem> {
+  let window_size = min_lines.max(1);
+  let files: Vec<FileLines> = files
+    .iter()
+    .filter_map(|file| {
+      let all_lines: Vec<&str> = file.content.as_str()?.lines().collect();
+      let lines = all_lines
+        .iter()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(idx, line)| Line {
+          number: idx as u32 + 1,
+          tokens: code_tokens(line).into_iter().map(hash).collect(),
+        })
+        .collect();
+      Some(FileLines {
+        name: &file.name,
+        all_lines,
+        lines,
+      })
+    })
+    .collect();
+
+  let windows: Vec<Window> = files
+    .iter()
+    .enumerate()
+    .flat_map(|(file, file_lines)| {
+      let count = (file_lines.lines.len() + 1).saturating_sub(window_size);
+      (0..count).map(move |start| Window { file, start })
+    })
+    .collect();
+
+  let detector = Detector {
+    files,
+    windows,
+    window_size,
+    threshold,
+  };
+
+  let mut blocks: Vec<(Block, Block, f64)> = detector
+    .runs()
+    .into_par_iter()
+    .map(|(a, b, length)| {
+      let (block_a, block_b) =
+        detector.extend(detector.block(a, length), detector.block(b, length));
+      let similarity = detector.similarity(&block_a, &block_b);
+      (block_a, block_b, similarity)
+    })
+    .collect();
+
+  // Largest and most similar blocks first
+  blocks.sort_by(|(a1, b1, similarity1), (a2, b2, similarity2)| {
+    let lines1 = (a1.end - a1.start).max(b1.end - b1.start);
+    let lines2 = (a2.end - a2.start).max(b2.end - b2.start);
+    lines2
+      .cmp(&lines1)
+      .then(similarity2.total_cmp(similarity1))
+      .then(
+        (a1.file, a1.start, b1.file, b1.start)
+          .cmp(&(a2.file, a2.start, b2.file, b2.start)),
+      )
+  });
+
+  // Only report a pair if at least one of its blocks isn't part of
+  // an already reported pair, so that a group of n similar blocks
+  // results in n - 1 pairs instead of one for each combination
+  let mut kept: Vec<Block> = Vec::new();
+  let mut result = Vec::new();
+  for (mut block_a, mut block_b, mut similarity) in blocks {
+    let is_kept =
+      |block: &Block| kept.iter().any(|other| block.overlaps(other));
+    if is_kept(&block_a) && is_kept(&block_b) {
+      // Blocks extended into the lines of other pairs
+      // still report the part between them
+      (block_a, block_b) = detector.uncovered_part(&block_a, &block_b, &kept);
+      if block_a.len() < window_size {
+        continue;
+      }
+      similarity = detector.similarity(&block_a, &block_b);
+      if similarity < threshold {
+        continue;
+      }
+    }
+
+    result.push(SimilarDuplicationItem {
+      content: detector.content(&block_a),
+      similarity,
+      locations: vec![detector.location(&block_a), detector.location(&block_b)],
+      differences: detector.differences(&block_a, &block_b),
+    });
+    kept.push(block_a);
+    kept.push(block_b);
+  }
+
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::MappedContent;
+
+  fn file(name: &str, content: &str) -> FileEntry {
+    FileEntry {
+      name: name.to_string(),
+      content: MappedContent::String(content.to_string()),
+    }
+  }
+
+  #[test]
+  fn test_code_tokens() {
+    assert_eq!(
+      code_tokens("let x_1 = foo(\"ä\");"),
+      ["let", "x_1", "=", "foo", "(", "\"", "ä", "\"", ")", ";"]
+    );
+  }
+
+  #[test]
+  fn test_lcs_matches() {
+    let (matched_a, matched_b) =
+      lcs_matches(&["a", "b", "c"], &["a", "c", "d"]);
+    assert_eq!(matched_a, [true, false, true]);
+    assert_eq!(matched_b, [true, true, false]);
+  }
+
+  #[test]
+  fn test_lcs_length() {
+    // Longest common subsequence by dynamic programming
+    fn expected(a: &[u8], b: &[u8]) -> usize {
+      let mut table = vec![vec![0; b.len() + 1]; a.len() + 1];
+      for idx_a in 0..a.len() {
+        for idx_b in 0..b.len() {
+          table[idx_a + 1][idx_b + 1] = if a[idx_a] == b[idx_b] {
+            table[idx_a][idx_b] + 1
+          } else {
+            table[idx_a][idx_b + 1].max(table[idx_a + 1][idx_b])
+          };
+        }
+      }
+      table[a.len()][b.len()]
+    }
+
+    let mut state = 1u64;
+    let mut sequence = |length: u64| -> Vec<u8> {
+      (0..length)
+        .map(|_| {
+          state = mix(state);
+          (state % 3) as u8
+        })
+        .collect()
+    };
+    for length in 0..40 {
+      let (a, b) = (sequence(length), sequence(length / 2 + 3));
+      let common = expected(&a, &b);
+      let edits = a.len() + b.len() - 2 * common;
+      assert_eq!(lcs_length(&a, &b, a.len() + b.len()), Some(common));
+      assert_eq!(lcs_length(&a, &b, edits), Some(common));
+      if edits > 0 {
+        assert_eq!(lcs_length(&a, &b, edits - 1), None);
+      }
+    }
+  }
+
+  #[test]
+  fn test_find_similar_blocks_large_files() {
+    let lines = |changed_line: usize, text: &str| -> String {
+      (0..1200)
+        .map(|idx| {
+          if idx == changed_line {
+            format!("{text}\n")
+          } else {
+            format!("let value_{idx} = compute({idx}, {});\n", idx % 7)
+          }
+        })
+        .collect()
+    };
+    let files = vec![
+      file("a.rs", &lines(600, "print(value);")),
+      file("b.rs", &lines(600, "log(other_value);")),
+    ];
+
+    // Blocks longer than the maximum are split up without gaps
+    let similar = find_similar_blocks(&files, 3, 0.9);
+
+    let lines: usize = similar
+      .iter()
+      .map(|item| item.content.lines().count())
+      .sum();
+    assert_eq!(lines, 1200);
+    assert!(similar.iter().all(|item| item.similarity >= 0.9));
+  }
+
+  #[test]
+  fn test_find_similar_blocks() {
+    let original = "\
+fn total(items: &[Item]) -> u32 {
+    let mut sum = 0;
+    for item in items {
+        sum += item.price * item.count;
+    }
+    sum
+}
+";
+    let edited = "\
+// Unrelated header
+fn total(items: &[Item]) -> u32 {
+    let mut result = 0;
+    for item in items {
+        result += item.price * item.count;
+    }
+    result
+}
+";
+    let files = vec![file("a.rs", original), file("b.rs", edited)];
+    let similar = find_similar_blocks(&files, 3, 0.8);
+
+    assert_eq!(similar.len(), 1);
+    let item = &similar[0];
+    assert!(item.similarity > 0.8 && item.similarity < 1.0);
+    assert_eq!(item.content, original.trim_end());
+    assert_eq!(
+      item.locations,
+      vec![
+        DuplicationLocation {
+          path: "a.rs".to_string(),
+          line: 1,
+          end_line: 7,
+        },
+        DuplicationLocation {
+          path: "b.rs".to_string(),
+          line: 2,
+          end_line: 8,
+        },
+      ]
+    );
+    let changed_lines: Vec<(&str, u32)> = item
+      .differences
+      .iter()
+      .map(|difference| (difference.path.as_str(), difference.line))
+      .collect();
+    assert_eq!(
+      changed_lines,
+      [
+        ("a.rs", 2),
+        ("a.rs", 4),
+        ("a.rs", 6),
+        ("b.rs", 3),
+        ("b.rs", 5),
+        ("b.rs", 7)
+      ]
+    );
+  }
+
+  #[test]
+  fn test_find_similar_blocks_threshold() {
+    let files = vec![
+      file("a.txt", "alpha beta gamma\ndelta epsilon zeta\n"),
+      file("b.txt", "one two three\nfour five six\n"),
+    ];
+
+    assert!(find_similar_blocks(&files, 2, 0.5).is_empty());
+  }
+
+  #[test]
+  fn test_find_similar_blocks_within_file() {
+    let content = "\
+let a = load(1);
+let b = parse(a);
+save(b);
+something else entirely
+let a = load(2);
+let b = parse(a);
+save(b);
+";
+    let similar = find_similar_blocks(&[file("a.rs", content)], 3, 0.9);
+
+    assert_eq!(similar.len(), 1);
+    let lines: Vec<u32> = similar[0]
+      .locations
+      .iter()
+      .map(|location| location.line)
+      .collect();
+    assert_eq!(lines, [1, 5]);
+  }
+}
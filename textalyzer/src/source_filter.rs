@@ -0,0 +1,378 @@
+use crate::lexer::{
+  is_word_char, lex, syntax_for_path, Syntax, Token, TokenKind,
+};
+use crate::types::{FileEntry, MappedContent, SkipOptions};
+use rayon::prelude::*;
+use std::ops::Range;
+
+/// Whether the line starting at `token` is an import statement
+fn is_import(text: &str, token: &Token, syntax: &Syntax) -> bool {
+  let rest = &text[token.offset..];
+  syntax.imports.iter().any(|prefix| {
+    rest.starts_with(prefix) && !rest[prefix.len()..].starts_with(is_word_char)
+  })
+}
+
+/// Number of tokens of the import statement starting at `tokens[0]`.
+/// It ends at the end of the first line where all brackets are closed.
+fn import_length(tokens: &[Token]) -> usize {
+  let mut depth = 0usize;
+  for (idx, token) in tokens.iter().enumerate() {
+    if token.kind == TokenKind::Punctuation {
+      match token.text {
+        "(" | "[" | "{" => depth += 1,
+        ")" | "]" | "}" => depth = depth.saturating_sub(1),
+        _ => {}
+      }
+    }
+    let ends_line = tokens
+      .get(idx + 1)
+      .is_none_or(|next| next.line > token.line);
+    if depth == 0 && ends_line {
+      return idx + 1;
+    }
+  }
+  tokens.len()
+}
+
+/// Phrases of which at least one must occur in a license header
+const LICENSE_MARKERS: [&str; 5] = [
+  "copyright",
+  "license",
+  "licence",
+  "all rights reserved",
+  "permission is hereby granted",
+];
+
+/// Number of tokens of the license header at the beginning of a file.
+/// It is the first block of comments without an empty line between them
+/// and must mention a copyright or a license.
+/// Doc comments like `//!` end it.
+fn license_header_length(tokens: &[Token]) -> usize {
+  let mut length = 0;
+  let mut next_line = None;
+  for token in tokens {
+    if token.kind != TokenKind::Comment
+      || token.text.starts_with("//!")
+      || token.text.starts_with("///")
+      || next_line.is_some_and(|next_line| token.line > next_line)
+    {
+      break;
+    }
+    next_line = Some(token.line + token.text.matches('\n').count() as u32 + 1);
+    length += 1;
+  }
+
+  let is_license = tokens[..length].iter().any(|token| {
+    let text = token.text.to_lowercase();
+    LICENSE_MARKERS.iter().any(|marker| text.contains(marker))
+  });
+  if is_license {
+    length
+  } else {
+    0
+  }
+}
+
+/// Byte ranges to remove and byte ranges of string literals
+fn skipped_ranges(
+  text: &str,
+  syntax: &Syntax,
+  options: &SkipOptions,
+) -> (Vec<Range<usize>>, Vec<Range<usize>>) {
+  let tokens = lex(text, syntax);
+  let range = |token: &Token| token.offset..token.offset + token.text.len();
+  let mut removed = Vec::new();
+  let mut strings = Vec::new();
+
+  if options.skip_license_headers {
+    let header_length = license_header_length(&tokens);
+    removed.extend(tokens[..header_length].iter().map(range));
+  }
+
+  let mut idx = 0;
+  let mut previous_line = 0;
+  while idx < tokens.len() {
+    let token = &tokens[idx];
+    let starts_line = token.line > previous_line;
+    previous_line = token.line;
+
+    if options.skip_imports && starts_line && is_import(text, token, syntax) {
+      let length = import_length(&tokens[idx..]);
+      let last = &tokens[idx + length - 1];
+      previous_line = last.line;
+      removed.push(token.offset..range(last).end);
+      idx += length;
+      continue;
+    }
+
+    match token.kind {
+      TokenKind::Comment if options.skip_comments => {
+        removed.push(range(token));
+      }
+      TokenKind::String if options.skip_strings => {
+        strings.push(range(token));
+      }
+      _ => {}
+    }
+    idx += 1;
+  }
+
+  (removed, strings)
+}
+
+/// Remove the parts of source code selected by `options`.
+///
+/// The language is chosen by the extension of `path`.
+/// Comments and import statements are removed
+/// and string literals are replaced by `""`.
+/// Line breaks of removed parts are kept,
+/// so that the line numbers don't change.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::source_filter::filter_source;
+/// use textalyzer::types::SkipOptions;
+///
+/// let options = SkipOptions {
+///   skip_comments: true,
+///   skip_strings: true,
+///   ..SkipOptions::default()
+/// };
+/// let code = "/* Greet\n   everyone */\nprint(\"Hello\") // Say hello\n";
+///
+/// assert_eq!(
+///   filter_source("main.js", code, &options),
+///   "\n\nprint(\"\")\n"
+/// );
+/// ```
+pub fn filter_source(path: &str, text: &str, options: &SkipOptions) -> String {
+  let (mut removed, strings) =
+    skipped_ranges(text, syntax_for_path(path), options);
+  let string_starts: Vec<usize> =
+    strings.iter().map(|range| range.start).collect();
+  removed.extend(strings);
+  removed.sort_by_key(|range| range.start);
+
+  let mut filtered = String::with_capacity(text.len());
+  let mut offset = 0;
+  let mut line = 0;
+  // Lines with whitespace left over before a removed part
+  let mut trimmed_lines = Vec::new();
+  for range in removed {
+    if range.start < offset {
+      continue;
+    }
+    filtered.push_str(&text[offset..range.start]);
+    line += text[offset..range.start].matches('\n').count();
+    if string_starts.binary_search(&range.start).is_ok() {
+      filtered.push_str("\"\"");
+    } else {
+      trimmed_lines.push(line);
+    }
+    let line_breaks = text[range.clone()].matches('\n').count();
+    filtered.extend(std::iter::repeat_n('\n', line_breaks));
+    line += line_breaks;
+    offset = range.end;
+  }
+  filtered.push_str(&text[offset..]);
+
+  filtered
+    .split('\n')
+    .enumerate()
+    .map(|(idx, line)| {
+      if trimmed_lines.binary_search(&idx).is_ok() {
+        line.trim_end()
+      } else {
+        line
+      }
+    })
+    .collect::<Vec<_>>()
+    .join("\n")
+}
+
+/// Remove the parts of all files selected by `options`
+/// with `filter_source`. Files which aren't valid UTF-8 are kept as is.
+pub fn filter_files(
+  files: Vec<FileEntry>,
+  options: &SkipOptions,
+) -> Vec<FileEntry> {
+  if options.is_empty() {
+    return files;
+  }
+
+  files
+    .into_par_iter()
+    .map(|file| match file.content.as_str() {
+      Some(text) => {
+        let filtered = filter_source(&file.name, text, options);
+        FileEntry {
+          content: MappedContent::String(filtered),
+          name: file.name,
+        }
+      }
+      None => file,
+    })
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::duplication::find_multi_line_duplications;
+
+  fn options(configure: fn(&mut SkipOptions)) -> SkipOptions {
+    let mut options = SkipOptions::default();
+    configure(&mut options);
+    options
+  }
+
+  #[test]
+  fn test_filter_source_comments() {
+    let code = "\
+# Compute the area
+def area(w, h):
+    \"\"\"Area of a
+    rectangle\"\"\"
+    return w * h  # Multiply
+";
+    let filtered = filter_source(
+      "shapes.py",
+      code,
+      &options(|options| options.skip_comments = true),
+    );
+
+    assert_eq!(filtered.lines().count(), code.lines().count());
+    assert_eq!(
+      filtered,
+      "\ndef area(w, h):\n    \"\"\"Area of a\n    rectangle\"\"\"\n    \
+       return w * h\n"
+    );
+  }
+
+  #[test]
+  fn test_filter_source_license_header() {
+    let code = "\
+// Copyright (c) 2024 Jane Doe
+// SPDX-License-Identifier: MIT
+
+fn main() {
+  // Start
+}
+";
+    let filtered = filter_source(
+      "main.rs",
+      code,
+      &options(|options| options.skip_license_headers = true),
+    );
+
+    assert_eq!(filtered, "\n\n\nfn main() {\n  // Start\n}\n");
+  }
+
+  #[test]
+  fn test_filter_source_license_header_only() {
+    let skip_headers = options(|options| options.skip_license_headers = true);
+
+    // Module docs and other comments at the beginning are kept
+    let code = "//! Parse the input\n// Helper\nfn main() {}\n";
+    assert_eq!(filter_source("main.rs", code, &skip_headers), code);
+
+    let code = "\
+// SPDX-License-Identifier: MIT
+//! Parse the input
+
+// Helper
+fn main() {}
+";
+    assert_eq!(
+      filter_source("main.rs", code, &skip_headers),
+      "\n//! Parse the input\n\n// Helper\nfn main() {}\n"
+    );
+  }
+
+  #[test]
+  fn test_filter_source_keeps_trailing_whitespace() {
+    let code = "let a = 1;  \nlet b = 2; // Two\n";
+    assert_eq!(
+      filter_source(
+        "main.rs",
+        code,
+        &options(|options| options.skip_comments = true)
+      ),
+      "let a = 1;  \nlet b = 2;\n"
+    );
+  }
+
+  #[test]
+  fn test_filter_source_imports() {
+    let code = "\
+use std::io;
+pub use crate::{
+  a,
+  b,
+};
+use_value(used);
+";
+    let filtered = filter_source(
+      "lib.rs",
+      code,
+      &options(|options| options.skip_imports = true),
+    );
+
+    assert_eq!(filtered, "\n\n\n\n\nuse_value(used);\n");
+
+    let code = "import {\n  x\n} from \"y\";\nimportant();\n";
+    let filtered = filter_source(
+      "main.ts",
+      code,
+      &options(|options| options.skip_imports = true),
+    );
+    assert_eq!(filtered, "\n\n\nimportant();\n");
+  }
+
+  #[test]
+  fn test_filter_source_strings() {
+    let skip_strings = options(|options| options.skip_strings = true);
+
+    assert_eq!(
+      filter_source("main.rs", "let a = \"x\";\nlet c = 'z';\n", &skip_strings),
+      "let a = \"\";\nlet c = \"\";\n"
+    );
+    assert_eq!(
+      filter_source("main.py", "a = \"\"\"x\ny\"\"\"\nb = 1\n", &skip_strings),
+      "a = \"\"\n\nb = 1\n"
+    );
+  }
+
+  #[test]
+  fn test_filter_files_license_headers() {
+    let header = "\
+/*
+ * Permission is hereby granted, free of charge, to any person
+ * obtaining a copy of this software and associated documentation
+ * files (the \"Software\"), to deal in the Software without restriction
+ */
+";
+    let file = |name: &str, code: &str| FileEntry {
+      name: name.to_string(),
+      content: MappedContent::String(format!("{header}{code}")),
+    };
+    let files = || {
+      vec![
+        file("a.c", "int a() {\n  return 1;\n}\n"),
+        file("b.c", "int b() {\n  return 2;\n}\n"),
+      ]
+    };
+
+    let is_header =
+      |(content, _): &(String, _)| content.contains("Permission is hereby");
+    assert!(find_multi_line_duplications(files()).iter().any(is_header));
+
+    let filtered = filter_files(
+      files(),
+      &options(|options| options.skip_license_headers = true),
+    );
+    assert!(!find_multi_line_duplications(filtered).iter().any(is_header));
+  }
+}
@@ -0,0 +1,260 @@
+use crate::output::format_table;
+use crate::readability::split_sentences;
+use crate::tokenizer::word_spans;
+use crate::types::{FileEntry, TextStats, TokenizerOptions};
+use rayon::prelude::*;
+use std::collections::HashSet;
+
+/// Average silent reading speed of adults in words per minute
+const READING_WORDS_PER_MINUTE: f64 = 238.0;
+
+/// Average speaking speed for presentations in words per minute
+const SPEAKING_WORDS_PER_MINUTE: f64 = 150.0;
+
+/// Raw counts of one or several texts,
+/// which can be merged before the averages are calculated.
+#[derive(Debug, Default)]
+struct StatsCounts {
+  words: usize,
+  word_characters: usize,
+  characters: usize,
+  characters_no_whitespace: usize,
+  sentences: usize,
+  paragraphs: usize,
+  unique_words: HashSet<String>,
+}
+
+impl StatsCounts {
+  fn from_text(text: &str) -> StatsCounts {
+    // Count numbers and compounds like "e-mail" as words like a word processor
+    let options = TokenizerOptions {
+      keep_numbers: true,
+      keep_hyphenated: true,
+      ..Default::default()
+    };
+    let words: Vec<&str> = word_spans(text, &options)
+      .into_iter()
+      .map(|(_, word)| word)
+      .collect();
+
+    StatsCounts {
+      words: words.len(),
+      word_characters: words
+        .iter()
+        .map(|word| word.chars().filter(|c| c.is_alphanumeric()).count())
+        .sum(),
+      characters: text.chars().count(),
+      characters_no_whitespace: text
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .count(),
+      sentences: split_sentences(text).len(),
+      paragraphs: count_paragraphs(text),
+      unique_words: words.iter().map(|word| word.to_lowercase()).collect(),
+    }
+  }
+
+  fn merge(mut self, other: &StatsCounts) -> StatsCounts {
+    self.words += other.words;
+    self.word_characters += other.word_characters;
+    self.characters += other.characters;
+    self.characters_no_whitespace += other.characters_no_whitespace;
+    self.sentences += other.sentences;
+    self.paragraphs += other.paragraphs;
+    self.unique_words.extend(other.unique_words.iter().cloned());
+    self
+  }
+
+  fn to_stats(&self) -> TextStats {
+    let average = |total: usize, count: usize| {
+      if count == 0 {
+        0.0
+      } else {
+        total as f64 / count as f64
+      }
+    };
+
+    TextStats {
+      words: self.words,
+      characters: self.characters,
+      characters_no_whitespace: self.characters_no_whitespace,
+      sentences: self.sentences,
+      paragraphs: self.paragraphs,
+      unique_words: self.unique_words.len(),
+      average_word_length: average(self.word_characters, self.words),
+      average_sentence_length: average(self.words, self.sentences),
+      reading_time_seconds: self.words as f64 / READING_WORDS_PER_MINUTE * 60.0,
+      speaking_time_seconds: self.words as f64 / SPEAKING_WORDS_PER_MINUTE
+        * 60.0,
+    }
+  }
+}
+
+/// Count the paragraphs of a text.
+/// Paragraphs are separated by one or more empty lines.
+fn count_paragraphs(text: &str) -> usize {
+  let mut paragraphs = 0;
+  let mut in_paragraph = false;
+
+  for line in text.lines() {
+    if line.trim().is_empty() {
+      in_paragraph = false;
+    } else if !in_paragraph {
+      in_paragraph = true;
+      paragraphs += 1;
+    }
+  }
+
+  paragraphs
+}
+
+/// Calculate general statistics like word and sentence counts of a text.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::stats::calculate_stats;
+///
+/// let stats = calculate_stats("The cat sat.\n\nThe cat ran away.");
+///
+/// assert_eq!(stats.words, 7);
+/// assert_eq!(stats.unique_words, 5);
+/// assert_eq!(stats.sentences, 2);
+/// assert_eq!(stats.paragraphs, 2);
+/// ```
+pub fn calculate_stats(text: &str) -> TextStats {
+  StatsCounts::from_text(text).to_stats()
+}
+
+/// Calculate the statistics of each file in parallel
+/// and the combined statistics of all files.
+pub fn calculate_file_stats(
+  files: &[FileEntry],
+) -> (Vec<(String, TextStats)>, TextStats) {
+  let counts: Vec<(String, StatsCounts)> = files
+    .par_iter()
+    .filter_map(|file| {
+      let text = file.content.as_str()?;
+      Some((file.name.clone(), StatsCounts::from_text(text)))
+    })
+    .collect();
+
+  let total = counts
+    .iter()
+    .fold(StatsCounts::default(), |total, (_, file_counts)| {
+      total.merge(file_counts)
+    })
+    .to_stats();
+
+  let file_stats = counts
+    .into_iter()
+    .map(|(name, file_counts)| (name, file_counts.to_stats()))
+    .collect();
+
+  (file_stats, total)
+}
+
+/// Format a duration in seconds as minutes and seconds, e.g. "3 min 20 s".
+fn format_duration(seconds: f64) -> String {
+  let seconds = seconds.round() as u64;
+  if seconds < 60 {
+    format!("{seconds} s")
+  } else {
+    format!("{} min {} s", seconds / 60, seconds % 60)
+  }
+}
+
+/// Format the statistics of a file into a string.
+pub fn format_stats(name: &str, stats: &TextStats) -> String {
+  let rows = [
+    ("Words", stats.words.to_string()),
+    ("Unique words", stats.unique_words.to_string()),
+    ("Characters", stats.characters.to_string()),
+    (
+      "Characters (no whitespace)",
+      stats.characters_no_whitespace.to_string(),
+    ),
+    ("Sentences", stats.sentences.to_string()),
+    ("Paragraphs", stats.paragraphs.to_string()),
+    (
+      "Average word length",
+      format!("{:.2}", stats.average_word_length),
+    ),
+    (
+      "Average sentence length",
+      format!("{:.2}", stats.average_sentence_length),
+    ),
+    ("Reading time", format_duration(stats.reading_time_seconds)),
+    (
+      "Speaking time",
+      format_duration(stats.speaking_time_seconds),
+    ),
+  ];
+
+  format_table(name, &rows)
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::MappedContent;
+
+  #[test]
+  fn test_calculate_stats() {
+    let stats = calculate_stats("Hello world!\nHello again.\n\n  New one.\n");
+
+    assert_eq!(stats.words, 6);
+    assert_eq!(stats.unique_words, 5);
+    assert_eq!(stats.characters, 38);
+    assert_eq!(stats.characters_no_whitespace, 29);
+    assert_eq!(stats.sentences, 3);
+    assert_eq!(stats.paragraphs, 2);
+    assert_eq!(stats.average_word_length, 26.0 / 6.0);
+    assert_eq!(stats.average_sentence_length, 2.0);
+  }
+
+  #[test]
+  fn test_calculate_stats_empty() {
+    let stats = calculate_stats("");
+    assert_eq!(stats, TextStats::default());
+  }
+
+  #[test]
+  fn test_calculate_file_stats_total() {
+    let files = vec![
+      FileEntry {
+        name: "file1.txt".to_string(),
+        content: MappedContent::String("One two three.".to_string()),
+      },
+      FileEntry {
+        name: "file2.txt".to_string(),
+        content: MappedContent::String("Three four.".to_string()),
+      },
+    ];
+
+    let (file_stats, total) = calculate_file_stats(&files);
+
+    assert_eq!(file_stats.len(), 2);
+    assert_eq!(file_stats[0].0, "file1.txt");
+    assert_eq!(file_stats[0].1.words, 3);
+    assert_eq!(total.words, 5);
+    assert_eq!(total.unique_words, 4);
+    assert_eq!(total.sentences, 2);
+    assert_eq!(total.paragraphs, 2);
+  }
+
+  #[test]
+  fn test_calculate_stats_cjk() {
+    let stats = calculate_stats("我们喜欢学习中文。我们学习。");
+
+    assert_eq!(stats.words, 6);
+    assert_eq!(stats.unique_words, 4);
+    assert_eq!(stats.sentences, 2);
+  }
+
+  #[test]
+  fn test_format_duration() {
+    assert_eq!(format_duration(12.4), "12 s");
+    assert_eq!(format_duration(200.0), "3 min 20 s");
+  }
+}
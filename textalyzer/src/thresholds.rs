@@ -0,0 +1,160 @@
+use crate::types::{DuplicatedLines, ThresholdOptions};
+use std::error::Error;
+use std::fmt;
+
+/// Amount of duplicated code used to check the thresholds
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DuplicationSummary {
+  pub duplications: usize,
+  /// Non-empty lines of all files which are covered by a duplication
+  /// as calculated by `duplication_report::build_report`
+  pub lines: DuplicatedLines,
+}
+
+impl fmt::Display for DuplicationSummary {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(
+      f,
+      "{} duplications with {} of {} lines duplicated ({:.2}%)",
+      self.duplications,
+      self.lines.duplicated_lines,
+      self.lines.lines,
+      self.lines.percentage
+    )
+  }
+}
+
+/// Error if the duplications exceed one or more thresholds
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThresholdExceeded {
+  pub summary: DuplicationSummary,
+  /// Descriptions of all exceeded thresholds
+  pub exceeded: Vec<String>,
+}
+
+impl fmt::Display for ThresholdExceeded {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "Duplication threshold exceeded: {}", self.summary)?;
+    for threshold in &self.exceeded {
+      write!(f, "\n  - {threshold}")?;
+    }
+    Ok(())
+  }
+}
+
+impl Error for ThresholdExceeded {}
+
+/// Check the summary against all thresholds which are set
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::thresholds::{check_thresholds, DuplicationSummary};
+/// use textalyzer::types::{DuplicatedLines, ThresholdOptions};
+///
+/// let summary = DuplicationSummary {
+///   duplications: 3,
+///   lines: DuplicatedLines::new(200, 30),
+/// };
+/// let thresholds = ThresholdOptions {
+///   max_duplications: Some(5),
+///   max_percentage: Some(10.0),
+///   ..ThresholdOptions::default()
+/// };
+///
+/// let error = check_thresholds(summary, &thresholds).unwrap_err();
+/// assert_eq!(
+///   error.exceeded,
+///   ["15.00% of the lines are duplicated, more than the maximum of 10%"]
+/// );
+/// ```
+pub fn check_thresholds(
+  summary: DuplicationSummary,
+  thresholds: &ThresholdOptions,
+) -> Result<(), ThresholdExceeded> {
+  let mut exceeded = Vec::new();
+
+  if let Some(max) = thresholds.max_duplications {
+    if summary.duplications > max {
+      exceeded.push(format!(
+        "{} duplications, more than the maximum of {max}",
+        summary.duplications
+      ));
+    }
+  }
+  if let Some(max) = thresholds.max_duplicated_lines {
+    if summary.lines.duplicated_lines > max {
+      exceeded.push(format!(
+        "{} duplicated lines, more than the maximum of {max}",
+        summary.lines.duplicated_lines
+      ));
+    }
+  }
+  if let Some(max) = thresholds.max_percentage {
+    if summary.lines.percentage > max {
+      exceeded.push(format!(
+        "{:.2}% of the lines are duplicated, more than the maximum of {max}%",
+        summary.lines.percentage
+      ));
+    }
+  }
+
+  if exceeded.is_empty() {
+    Ok(())
+  } else {
+    Err(ThresholdExceeded { summary, exceeded })
+  }
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::duplication_report::build_report;
+  use std::collections::HashMap;
+
+  #[test]
+  fn test_check_thresholds_with_overlapping_copies() {
+    // The copies of "a\nb\na\nb" in "a\nb\na\nb\na\nb" share two lines
+    let file_lines = HashMap::from([("a.txt".to_string(), (1..=6).collect())]);
+    let report = build_report(&file_lines, [("a.txt", 1, 4), ("a.txt", 3, 6)]);
+    let summary = DuplicationSummary {
+      duplications: 1,
+      lines: report.total,
+    };
+    let thresholds = ThresholdOptions {
+      max_duplicated_lines: Some(6),
+      max_percentage: Some(100.0),
+      ..ThresholdOptions::default()
+    };
+
+    assert_eq!(summary.lines, DuplicatedLines::new(6, 6));
+    assert!(check_thresholds(summary, &thresholds).is_ok());
+  }
+
+  #[test]
+  fn test_check_thresholds() {
+    let summary = DuplicationSummary {
+      duplications: 4,
+      lines: DuplicatedLines::new(100, 40),
+    };
+    let thresholds =
+      |max_duplications, max_duplicated_lines| ThresholdOptions {
+        max_duplications,
+        max_duplicated_lines,
+        max_percentage: None,
+      };
+
+    assert!(check_thresholds(summary, &thresholds(None, None)).is_ok());
+    assert!(check_thresholds(summary, &thresholds(Some(4), Some(40))).is_ok());
+
+    let error =
+      check_thresholds(summary, &thresholds(Some(3), Some(39))).unwrap_err();
+    assert_eq!(
+      error.to_string(),
+      "Duplication threshold exceeded: \
+        4 duplications with 40 of 100 lines duplicated (40.00%)\n  \
+        - 4 duplications, more than the maximum of 3\n  \
+        - 40 duplicated lines, more than the maximum of 39"
+    );
+  }
+}
@@ -0,0 +1,307 @@
+use crate::duplication::remove_common_indent;
+use crate::lexer::{lex, syntax_for_path, TokenKind};
+use crate::types::{Duplication, FileEntry};
+use rayon::prelude::*;
+use std::collections::hash_map::DefaultHasher;
+use std::collections::HashMap;
+use std::hash::{Hash, Hasher};
+
+/// Default minimum number of tokens of a token-based duplication
+pub const DEFAULT_MIN_TOKENS: usize = 50;
+
+/// Base of the Rabin-Karp rolling hash
+const HASH_BASE: u64 = 0x100000001b3;
+
+/// Index of a file and position of a token in it
+type Location = (usize, usize);
+
+/// Tokens of a file without comments
+struct SourceTokens<'a> {
+  name: &'a str,
+  lines: Vec<&'a str>,
+  /// Hash of each token, which is the same for all identifiers
+  /// and all literals if they are normalized
+  ids: Vec<u64>,
+  /// Line of each token
+  token_lines: Vec<u32>,
+}
+
+fn token_id(kind: TokenKind, text: &str, normalize: bool) -> u64 {
+  let mut hasher = DefaultHasher::new();
+  kind.hash(&mut hasher);
+  let is_normalized = normalize
+    && matches!(
+      kind,
+      TokenKind::Identifier | TokenKind::Number | TokenKind::String
+    );
+  if !is_normalized {
+    text.hash(&mut hasher);
+  }
+  hasher.finish()
+}
+
+fn tokenize_file(
+  file: &FileEntry,
+  normalize: bool,
+) -> Option<SourceTokens<'_>> {
+  let text = file.content.as_str()?;
+  let tokens: Vec<_> = lex(text, syntax_for_path(&file.name))
+    .into_iter()
+    .filter(|token| token.kind != TokenKind::Comment)
+    .collect();
+
+  Some(SourceTokens {
+    name: &file.name,
+    lines: text.lines().collect(),
+    ids: tokens
+      .iter()
+      .map(|token| token_id(token.kind, token.text, normalize))
+      .collect(),
+    token_lines: tokens.iter().map(|token| token.line).collect(),
+  })
+}
+
+/// Rabin-Karp hashes of all windows of `length` tokens
+fn rolling_hashes(ids: &[u64], length: usize) -> Vec<u64> {
+  if ids.len() < length {
+    return Vec::new();
+  }
+
+  let highest_power =
+    (1..length).fold(1u64, |power, _| power.wrapping_mul(HASH_BASE));
+  let mut hash = ids[..length].iter().fold(0u64, |hash, id| {
+    hash.wrapping_mul(HASH_BASE).wrapping_add(*id)
+  });
+
+  let mut hashes = Vec::with_capacity(ids.len() - length + 1);
+  hashes.push(hash);
+  for idx in length..ids.len() {
+    hash = hash
+      .wrapping_sub(ids[idx - length].wrapping_mul(highest_power))
+      .wrapping_mul(HASH_BASE)
+      .wrapping_add(ids[idx]);
+    hashes.push(hash);
+  }
+  hashes
+}
+
+/// Find duplicated sequences of at least `min_tokens` tokens
+/// across files or within the same file.
+///
+/// Files are split into tokens according to the language
+/// of their file extension, so formatting and comments don't matter.
+/// If `normalize` is true, all identifiers and all literals are
+/// considered equal, which also finds copies with renamed variables.
+/// Windows of `min_tokens` tokens are indexed by a rolling hash
+/// and matching windows are extended as far as the tokens are equal.
+/// As with `find_multi_line_duplications`, only the longest of
+/// overlapping duplications is kept.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::token_duplication::find_token_duplications;
+/// use textalyzer::types::{FileEntry, MappedContent};
+///
+/// let file = |name: &str, content: &str| FileEntry {
+///   name: name.to_string(),
+///   content: MappedContent::String(content.to_string()),
+/// };
+/// let files = || {
+///   vec![
+///     file("a.rs", "let total = price * count;"),
+///     file("b.rs", "let sum =\n  price * amount; // Sum"),
+///   ]
+/// };
+///
+/// assert!(find_token_duplications(files(), 5, false).is_empty());
+///
+/// let duplications = find_token_duplications(files(), 5, true);
+/// assert_eq!(duplications[0].0, "let total = price * count;");
+/// assert_eq!(duplications[0].1.len(), 2);
+/// ```
+pub fn find_token_duplications(
+  files: Vec<FileEntry>,
+  min_tokens: usize,
+  normalize: bool,
+) -> Vec<Duplication> {
+  let min_tokens = min_tokens.max(1);
+  let sources: Vec<SourceTokens> = files
+    .par_iter()
+    .filter_map(|file| tokenize_file(file, normalize))
+    .collect();
+
+  let window_hashes: Vec<Vec<u64>> = sources
+    .par_iter()
+    .map(|source| rolling_hashes(&source.ids, min_tokens))
+    .collect();
+
+  let mut windows: HashMap<u64, Vec<Location>> = HashMap::new();
+  for (file, hashes) in window_hashes.iter().enumerate() {
+    for (position, hash) in hashes.iter().enumerate() {
+      windows.entry(*hash).or_default().push((file, position));
+    }
+  }
+
+  let window = |(file, position): Location| {
+    &sources[file].ids[position..position + min_tokens]
+  };
+
+  // Pair each window with the first equal window and extend the match.
+  // Matches are grouped by their first window and length.
+  let mut matches: HashMap<(Location, usize), Vec<Location>> = HashMap::new();
+  for locations in windows.values().filter(|locations| locations.len() > 1) {
+    let mut remaining = locations.clone();
+    while let Some(&first) = remaining.first() {
+      let (equal, other): (Vec<_>, Vec<_>) = remaining
+        .into_iter()
+        .partition(|location| window(*location) == window(first));
+      remaining = other;
+
+      let (first_file, first_position) = first;
+      let first_ids = &sources[first_file].ids;
+      for &(file, position) in &equal[1..] {
+        let ids = &sources[file].ids;
+        // Only start at the beginning of a duplication
+        if first_position > 0
+          && position > 0
+          && first_ids[first_position - 1] == ids[position - 1]
+        {
+          continue;
+        }
+        // Don't let a duplication overlap itself
+        let max_length = if file == first_file {
+          position - first_position
+        } else {
+          usize::MAX
+        };
+        if max_length < min_tokens {
+          continue;
+        }
+
+        let mut length = min_tokens;
+        while length < max_length
+          && first_position + length < first_ids.len()
+          && position + length < ids.len()
+          && first_ids[first_position + length] == ids[position + length]
+        {
+          length += 1;
+        }
+        matches
+          .entry((first, length))
+          .or_insert_with(|| vec![first])
+          .push((file, position));
+      }
+    }
+  }
+
+  // Longest duplications first
+  let mut matches: Vec<_> = matches.into_iter().collect();
+  matches.sort_by(|((first_a, length_a), _), ((first_b, length_b), _)| {
+    length_b.cmp(length_a).then(first_a.cmp(first_b))
+  });
+
+  let mut used: Vec<Vec<bool>> = sources
+    .iter()
+    .map(|source| vec![false; source.ids.len()])
+    .collect();
+  let mut result = Vec::new();
+
+  for ((_, length), locations) in matches {
+    let free_locations: Vec<Location> = locations
+      .into_iter()
+      .filter(|&(file, position)| {
+        !used[file][position..position + length].contains(&true)
+      })
+      .collect();
+    if free_locations.len() < 2 {
+      continue;
+    }
+    for &(file, position) in &free_locations {
+      used[file][position..position + length].fill(true);
+    }
+
+    let (first_file, first_position) = free_locations[0];
+    let source = &sources[first_file];
+    let first_line = source.token_lines[first_position] as usize;
+    let last_line = source.token_lines[first_position + length - 1] as usize;
+    let content =
+      remove_common_indent(&source.lines[first_line - 1..last_line]);
+
+    let locations = free_locations
+      .into_iter()
+      .map(|(file, position)| {
+        let source = &sources[file];
+        (
+          source.name.to_string(),
+          source.token_lines[position],
+          source.token_lines[position + length - 1],
+        )
+      })
+      .collect();
+    result.push((content, locations));
+  }
+
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+  use crate::types::MappedContent;
+
+  fn file(name: &str, content: &str) -> FileEntry {
+    FileEntry {
+      name: name.to_string(),
+      content: MappedContent::String(content.to_string()),
+    }
+  }
+
+  #[test]
+  fn test_rolling_hashes() {
+    let ids = [1, 2, 3, 1, 2, 3];
+    let hashes = rolling_hashes(&ids, 3);
+
+    assert_eq!(hashes.len(), 4);
+    assert_eq!(hashes[0], hashes[3]);
+    assert_ne!(hashes[0], hashes[1]);
+    assert!(rolling_hashes(&ids, 7).is_empty());
+  }
+
+  #[test]
+  fn test_find_token_duplications() {
+    let original = "\
+fn area(width: u32, height: u32) -> u32 {
+    // Multiply the sides
+    width * height
+}
+";
+    let renamed = "\
+fn main() {}
+
+fn size(w: u32, h: u32) -> u32 { w * h }
+";
+    let files = || vec![file("a.rs", original), file("b.rs", renamed)];
+
+    assert!(find_token_duplications(files(), 10, false).is_empty());
+
+    let duplications = find_token_duplications(files(), 10, true);
+    assert_eq!(duplications.len(), 1);
+    let (content, locations) = &duplications[0];
+    assert_eq!(content, original.trim_end());
+    assert_eq!(
+      locations,
+      &vec![("a.rs".to_string(), 1, 4), ("b.rs".to_string(), 3, 3)]
+    );
+  }
+
+  #[test]
+  fn test_find_token_duplications_min_tokens() {
+    let content = "x = compute(a, b)\ny = 1\nx = compute(a, b)\n";
+    let files = || vec![file("a.py", content)];
+
+    // "x = compute(a, b)" has 8 tokens
+    assert_eq!(find_token_duplications(files(), 8, false).len(), 1);
+    assert!(find_token_duplications(files(), 9, false).is_empty());
+  }
+}
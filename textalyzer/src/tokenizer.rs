@@ -0,0 +1,305 @@
+use crate::language::Language;
+use crate::segmenter::{detect_cjk_language, script_of, segment};
+use crate::segmenter::{Dictionary, Script};
+use crate::types::TokenizerOptions;
+use unicode_segmentation::UnicodeSegmentation;
+
+fn is_hyphen(segment: &str) -> bool {
+  matches!(segment, "-" | "\u{2010}" | "\u{2011}")
+}
+
+fn is_apostrophe(character: char) -> bool {
+  matches!(character, '\'' | '\u{2019}')
+}
+
+/// Thai repetition and abbreviation marks, which are letters in Unicode
+fn is_thai_mark(character: char) -> bool {
+  matches!(character, 'ๆ' | 'ฯ')
+}
+
+fn is_word(segment: &str) -> bool {
+  segment
+    .chars()
+    .any(|character| character.is_alphanumeric() && !is_thai_mark(character))
+}
+
+/// Split a text into words with their byte offsets in the text.
+///
+/// Words are determined by the Unicode word boundaries of UAX #29,
+/// so "don't" stays one word and punctuation is dropped.
+/// Chinese, Japanese, and Thai, which are written without spaces,
+/// are split with a bundled dictionary.
+/// The language of Chinese characters is detected from the text
+/// unless it is set in the options.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::tokenizer::word_spans;
+/// use textalyzer::types::TokenizerOptions;
+///
+/// let spans = word_spans("Don't e-mail", &TokenizerOptions::default());
+///
+/// assert_eq!(spans, vec![(0, "Don't"), (6, "e"), (8, "mail")]);
+/// ```
+pub fn word_spans<'a>(
+  text: &'a str,
+  options: &TokenizerOptions,
+) -> Vec<(usize, &'a str)> {
+  let mut spans = Vec::new();
+  let mut cjk_language = None;
+
+  for (run_start, run, script) in script_runs(text) {
+    let dictionary = match script {
+      None => {
+        spans.extend(
+          alphabetic_word_spans(run, options)
+            .into_iter()
+            .map(|(start, word)| (run_start + start, word)),
+        );
+        continue;
+      }
+      Some(Script::Thai) => Dictionary::for_language(Language::Thai),
+      Some(Script::Cjk) => {
+        let language =
+          *cjk_language.get_or_insert_with(|| match options.language {
+            Some(lang @ (Language::Chinese | Language::Japanese)) => lang,
+            _ => detect_cjk_language(text),
+          });
+        Dictionary::for_language(language)
+      }
+    };
+
+    if let Some(dictionary) = dictionary {
+      spans.extend(
+        segment(run, dictionary)
+          .into_iter()
+          .map(|(start, word)| (run_start + start, word)),
+      );
+    }
+  }
+
+  spans
+}
+
+/// Split a text into runs of characters
+/// which do or don't need dictionary-based segmentation.
+fn script_runs(text: &str) -> Vec<(usize, &str, Option<Script>)> {
+  let mut runs = Vec::new();
+  let mut run_start = 0;
+  let mut run_script = None;
+
+  for (idx, character) in text.char_indices() {
+    let script = script_of(character);
+    if script != run_script {
+      if idx > run_start {
+        runs.push((run_start, &text[run_start..idx], run_script));
+      }
+      run_start = idx;
+      run_script = script;
+    }
+  }
+
+  if text.len() > run_start {
+    runs.push((run_start, &text[run_start..], run_script));
+  }
+
+  runs
+}
+
+/// Split a text written with spaces between words at UAX #29 boundaries.
+fn alphabetic_word_spans<'a>(
+  text: &'a str,
+  options: &TokenizerOptions,
+) -> Vec<(usize, &'a str)> {
+  let segments: Vec<(usize, &str)> = text.split_word_bound_indices().collect();
+  let mut spans = Vec::new();
+  let mut idx = 0;
+
+  while idx < segments.len() {
+    let (start, segment) = segments[idx];
+    idx += 1;
+
+    if !is_word(segment) {
+      continue;
+    }
+
+    let mut end = start + segment.len();
+
+    // Join compounds like "e-mail" or "COVID-19"
+    if options.keep_hyphenated {
+      while idx + 1 < segments.len()
+        && is_hyphen(segments[idx].1)
+        && is_word(segments[idx + 1].1)
+      {
+        end = segments[idx + 1].0 + segments[idx + 1].1.len();
+        idx += 2;
+      }
+    }
+
+    let word = &text[start..end];
+
+    if !options.keep_numbers && !word.chars().any(char::is_alphabetic) {
+      continue;
+    }
+
+    if options.split_apostrophes {
+      let mut parts = Vec::new();
+      let mut part_start = 0;
+      for (char_idx, character) in word.char_indices() {
+        if is_apostrophe(character) {
+          parts.push((start + part_start, &word[part_start..char_idx]));
+          part_start = char_idx + character.len_utf8();
+        }
+      }
+      parts.push((start + part_start, &word[part_start..]));
+      spans.extend(parts.into_iter().filter(|(_, part)| is_word(part)));
+    } else {
+      spans.push((start, word));
+    }
+  }
+
+  spans
+}
+
+/// Split a text into lowercase words.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::tokenizer::tokenize;
+/// use textalyzer::types::TokenizerOptions;
+///
+/// let options = TokenizerOptions {
+///   keep_numbers: true,
+///   keep_hyphenated: true,
+///   ..Default::default()
+/// };
+///
+/// assert_eq!(
+///   tokenize("COVID-19 isn't over.", &options),
+///   vec!["covid-19", "isn't", "over"],
+/// );
+/// ```
+pub fn tokenize(text: &str, options: &TokenizerOptions) -> Vec<String> {
+  word_spans(text, options)
+    .into_iter()
+    .map(|(_, word)| word.to_lowercase())
+    .collect()
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  #[test]
+  fn test_tokenize_default() {
+    let text = "Don't e-mail COVID-19 data in 2024, Winston’s 3.14 mp3s.";
+    let words = tokenize(text, &TokenizerOptions::default());
+
+    assert_eq!(
+      words,
+      vec![
+        "don't",
+        "e",
+        "mail",
+        "covid",
+        "data",
+        "in",
+        "winston’s",
+        "mp3s"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_tokenize_with_numbers() {
+    let options = TokenizerOptions {
+      keep_numbers: true,
+      ..Default::default()
+    };
+    let words = tokenize("In 2024 about 3.14 or 1,000 people", &options);
+
+    assert_eq!(
+      words,
+      vec!["in", "2024", "about", "3.14", "or", "1,000", "people"]
+    );
+  }
+
+  #[test]
+  fn test_tokenize_with_hyphenated_compounds() {
+    let options = TokenizerOptions {
+      keep_hyphenated: true,
+      ..Default::default()
+    };
+    let words = tokenize("A well-known e-mail - not a dash-", &options);
+
+    assert_eq!(words, vec!["a", "well-known", "e-mail", "not", "a", "dash"]);
+  }
+
+  #[test]
+  fn test_tokenize_with_split_apostrophes() {
+    let options = TokenizerOptions {
+      split_apostrophes: true,
+      ..Default::default()
+    };
+    let text = "Don't stop, Winston’s here";
+
+    assert_eq!(
+      tokenize(text, &options),
+      vec!["don", "t", "stop", "winston", "s", "here"]
+    );
+    assert_eq!(word_spans(text, &options)[4..], [(22, "s"), (24, "here")]);
+  }
+
+  #[test]
+  fn test_tokenize_german() {
+    let text = "Da stopfte, wenn's Mittag vom Thurme scholl, \
+      ick hebb' ne Birn.";
+    let words = tokenize(text, &TokenizerOptions::default());
+
+    assert_eq!(
+      words,
+      vec![
+        "da", "stopfte", "wenn's", "mittag", "vom", "thurme", "scholl", "ick",
+        "hebb", "ne", "birn"
+      ]
+    );
+  }
+
+  #[test]
+  fn test_tokenize_cjk_and_thai() {
+    let options = TokenizerOptions::default();
+
+    assert_eq!(
+      tokenize("Textalyzer: 我们喜欢学习中文。", &options),
+      vec!["textalyzer", "我们", "喜欢", "学习", "中文"]
+    );
+    assert_eq!(
+      tokenize("私は東京の大学の学生です", &options),
+      vec!["私", "は", "東京", "の", "大学", "の", "学生", "です"]
+    );
+    assert_eq!(
+      word_spans("สวัสดีครับ คุณ", &options),
+      vec![(0, "สวัสดี"), (18, "ครับ"), (31, "คุณ")]
+    );
+    // Thai digits and punctuation aren't segmented as words
+    assert_eq!(tokenize("เด็กๆ ไป ๒๕๖๗ ฯ", &options), vec!["เด็ก", "ไป"]);
+    let keep_numbers = TokenizerOptions {
+      keep_numbers: true,
+      ..Default::default()
+    };
+    assert_eq!(tokenize("ปี ๒๕๖๗", &keep_numbers), vec!["ปี", "๒๕๖๗"]);
+  }
+
+  #[test]
+  fn test_tokenize_cjk_with_language() {
+    // "中" is in the Japanese dictionary, but not in the Chinese one
+    let options = TokenizerOptions {
+      language: Some(Language::Japanese),
+      ..Default::default()
+    };
+    assert_eq!(tokenize("中国", &options), vec!["中", "国"]);
+    assert_eq!(tokenize("中国", &TokenizerOptions::default()), vec!["中国"]);
+  }
+}
@@ -1,14 +1,42 @@
 extern crate clap;
 extern crate memmap2;
 
-use self::clap::Subcommand;
-use serde::Serialize;
+use self::clap::{Args, Subcommand, ValueEnum};
+use crate::language::Language;
+use serde::{Deserialize, Serialize};
 
 #[derive(Subcommand)]
 pub enum Command {
-  /// Prints a histogram of word frequency in a file
+  /// Prints a histogram of word frequency in all files at the given paths
   Histogram {
-    filepath: String,
+    /// Paths to files or directories to analyze (`-` for stdin)
+    paths: Vec<String>,
+    /// Count sequences of N consecutive words instead of single words
+    #[clap(long, default_value = "1")]
+    ngram: usize,
+    /// Leave out stop words of a bundled language list
+    /// (english, german, french, spanish, chinese, japanese, thai)
+    /// or of a word list file
+    #[clap(long, value_name = "LANG|FILE")]
+    stop_words: Option<String>,
+    /// Count inflected forms like "runs" and "running" as one stem
+    #[clap(long)]
+    stem: bool,
+    /// Language of the text (english, german, french, spanish,
+    /// chinese, japanese, thai). Detected from the files if not set.
+    /// Chinese, Japanese, and Thai are split into words on a best-effort
+    /// basis with a small bundled dictionary of common words.
+    #[clap(long)]
+    lang: Option<String>,
+    /// Show which words were counted for each stem
+    #[clap(long)]
+    show_forms: bool,
+    #[clap(flatten)]
+    tokenizer: TokenizerOptions,
+    #[clap(flatten)]
+    histogram: HistogramOptions,
+    #[clap(flatten)]
+    bars: BarOptions,
     /// Output the histogram data as JSON
     #[clap(long)]
     json: bool,
@@ -20,6 +48,40 @@ pub enum Command {
     /// Minimum number of non-empty lines to be considered a duplication
     #[clap(long, default_value = "3")]
     min_lines: usize,
+    /// Find nearly identical blocks instead of only exact ones,
+    /// whose token similarity is at least this value between 0 and 1
+    /// (e.g. 0.85)
+    #[clap(
+      long,
+      value_name = "THRESHOLD",
+      conflicts_with_all = ["min_tokens", "normalize"]
+    )]
+    similarity: Option<f64>,
+    /// Compare the tokens of source code (detected by file extension)
+    /// instead of lines, ignoring formatting and comments,
+    /// and report duplications with at least this many tokens
+    /// (default with --normalize: 50)
+    #[clap(long, value_name = "N")]
+    min_tokens: Option<usize>,
+    /// Consider all identifiers and all literals as equal
+    /// to also find copies with renamed variables or changed values
+    #[clap(long)]
+    normalize: bool,
+    #[clap(flatten)]
+    skip: SkipOptions,
+    /// Only report duplications which aren't recorded in this baseline file
+    #[clap(long, value_name = "FILE")]
+    baseline: Option<String>,
+    /// Record all found duplications in the baseline file
+    /// instead of reporting them
+    #[clap(long, requires = "baseline")]
+    write_baseline: bool,
+    #[clap(flatten)]
+    thresholds: ThresholdOptions,
+    /// Also report the share of duplicated lines
+    /// per file, per directory, and in total
+    #[clap(long)]
+    report: bool,
     /// Only show the file paths with duplications, not the duplicated content
     #[clap(long)]
     files_only: bool,
@@ -27,16 +89,289 @@ pub enum Command {
     #[clap(long)]
     json: bool,
   },
+  /// Prints readability scores of all files at the given paths
+  Readability {
+    /// Paths to files or directories to analyze
+    paths: Vec<String>,
+    /// Output the readability scores as JSON
+    #[clap(long)]
+    json: bool,
+  },
+  /// Prints general statistics like word and sentence counts
+  Stats {
+    /// Paths to files or directories to analyze
+    paths: Vec<String>,
+    /// Output the statistics as JSON
+    #[clap(long)]
+    json: bool,
+  },
+  /// Prints vocabulary richness and lexical diversity metrics of each file
+  Diversity {
+    /// Paths to files or directories to analyze
+    paths: Vec<String>,
+    /// Number of words in the window of the moving-average type-token ratio
+    #[clap(long, default_value = "50")]
+    window: usize,
+    /// Output the metrics as JSON
+    #[clap(long)]
+    json: bool,
+  },
+  /// Fits Zipf's law to the word frequencies of all files at the given paths
+  Zipf {
+    /// Paths to files or directories to analyze (`-` for stdin)
+    paths: Vec<String>,
+    /// Number of words which deviate most from the fit to show
+    #[clap(long, default_value = "10")]
+    deviations: usize,
+    /// Export the rank/frequency pairs as CSV
+    #[clap(long)]
+    csv: bool,
+    /// Plot the rank/frequency pairs on a log-log scale
+    #[clap(long)]
+    plot: bool,
+    /// Width of the plot (default: width of the terminal)
+    #[clap(long, value_name = "COLUMNS")]
+    width: Option<usize>,
+    /// Output the fit as JSON
+    #[clap(long)]
+    json: bool,
+  },
+  /// Prints the most distinctive terms of each file by their TF-IDF score
+  Keywords {
+    /// Paths to the documents or directories of documents to analyze
+    paths: Vec<String>,
+    /// Number of keywords to show per document
+    #[clap(long, default_value = "10")]
+    top: usize,
+    /// Leave out stop words of a bundled language list
+    /// (english, german, french, spanish, chinese, japanese, thai)
+    /// or of a word list file
+    #[clap(long, value_name = "LANG|FILE")]
+    stop_words: Option<String>,
+    #[clap(flatten)]
+    tokenizer: TokenizerOptions,
+    /// Output the keywords as JSON
+    #[clap(long)]
+    json: bool,
+  },
+  /// Prints word pairs which occur together more often than by chance
+  Collocations {
+    /// Paths to files or directories to analyze (`-` for stdin)
+    paths: Vec<String>,
+    /// Association measure to rank the word pairs by
+    #[clap(long, value_enum, default_value_t)]
+    measure: CollocationMeasure,
+    /// Only consider word pairs which occur at least this often
+    #[clap(long, default_value = "3")]
+    min_count: i32,
+    /// Number of word pairs to show
+    #[clap(long, default_value = "20")]
+    top: usize,
+    /// Leave out word pairs with a stop word of a bundled language list
+    /// (english, german, french, spanish, chinese, japanese, thai)
+    /// or of a word list file
+    #[clap(long, value_name = "LANG|FILE")]
+    stop_words: Option<String>,
+    #[clap(flatten)]
+    tokenizer: TokenizerOptions,
+    /// Output the word pairs as JSON
+    #[clap(long)]
+    json: bool,
+  },
+  /// Compares the word frequencies of two texts or corpora
+  CompareFrequency {
+    /// Path to the first file or directory (`-` for stdin)
+    a: String,
+    /// Path to the second file or directory (`-` for stdin)
+    b: String,
+    /// Keyness measure to rank the overrepresented words by
+    #[clap(long, value_enum, default_value_t)]
+    measure: KeynessMeasure,
+    /// Number of words to show per list
+    #[clap(long, default_value = "20")]
+    top: usize,
+    /// Leave out stop words of a bundled language list
+    /// (english, german, french, spanish, chinese, japanese, thai)
+    /// or of a word list file
+    #[clap(long, value_name = "LANG|FILE")]
+    stop_words: Option<String>,
+    #[clap(flatten)]
+    tokenizer: TokenizerOptions,
+    /// Output the comparison as JSON
+    #[clap(long)]
+    json: bool,
+  },
+  /// Prints each occurrence of a word or phrase with its context
+  Concordance {
+    /// Word or phrase to search for (case-insensitive)
+    word: String,
+    /// Paths to files or directories to search (`-` for stdin)
+    paths: Vec<String>,
+    /// Number of words to show before and after each occurrence
+    #[clap(long, default_value = "5")]
+    context: usize,
+    #[clap(flatten)]
+    tokenizer: TokenizerOptions,
+    /// Output the occurrences as JSON
+    #[clap(long)]
+    json: bool,
+  },
+  /// Compares letter frequencies with the reference table of a language
+  CharFrequency {
+    /// Paths to files or directories to analyze
+    paths: Vec<String>,
+    /// Language of the reference table (english, german, french, spanish)
+    #[clap(long, default_value = "english")]
+    lang: String,
+    /// Output the letter frequencies as JSON
+    #[clap(long)]
+    json: bool,
+  },
+  /// Detects the language of each file at the given paths
+  DetectLanguage {
+    /// Paths to files or directories to analyze
+    paths: Vec<String>,
+    /// Output the detected languages as JSON
+    #[clap(long)]
+    json: bool,
+  },
   /// Analyzes and prints a histogram of line lengths in source files
   LineLength {
     /// Paths to files or directories to scan
     paths: Vec<String>,
+    #[clap(flatten)]
+    bars: BarOptions,
     /// Output the histogram data as JSON
     #[clap(long)]
     json: bool,
   },
 }
 
+/// Order of the entries of a histogram
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum SortOrder {
+  /// Most frequent entries first
+  #[default]
+  Count,
+  /// Alphabetically
+  Alpha,
+  /// Longest entries first
+  Length,
+}
+
+/// Statistical measure of how strongly two words are associated
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CollocationMeasure {
+  /// Dunning log-likelihood ratio, reliable for rare word pairs
+  #[default]
+  LogLikelihood,
+  /// Pointwise mutual information, favors rare but exclusive word pairs
+  Pmi,
+}
+
+/// Statistical measure of how much more frequent a word is in one text
+#[derive(ValueEnum, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum KeynessMeasure {
+  /// Dunning log-likelihood ratio
+  #[default]
+  LogLikelihood,
+  /// Pearson's chi-squared statistic
+  ChiSquared,
+}
+
+/// Options to select and order the entries of a histogram
+#[derive(Args, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct HistogramOptions {
+  /// Only show the first N entries
+  #[clap(long, value_name = "N")]
+  pub top: Option<usize>,
+  /// Only show entries which occur at least N times
+  #[clap(long, value_name = "N")]
+  pub min_count: Option<i32>,
+  /// Order of the entries
+  #[clap(long, value_enum, default_value_t)]
+  pub sort: SortOrder,
+  /// Reverse the order of the entries
+  #[clap(long)]
+  pub reverse: bool,
+}
+
+/// Options to configure how the bars of a histogram are drawn
+#[derive(Args, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct BarOptions {
+  /// Maximum width of the histogram (default: width of the terminal)
+  #[clap(long, value_name = "COLUMNS")]
+  pub width: Option<usize>,
+  /// Draw bars with fractional block characters for a finer resolution
+  #[clap(long)]
+  pub fractional_bars: bool,
+}
+
+/// Options to configure how a text is split into words
+#[derive(Args, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct TokenizerOptions {
+  /// Count numbers like "1984" or "3.14" as words
+  #[clap(long)]
+  pub keep_numbers: bool,
+  /// Keep hyphenated compounds like "e-mail" as one word
+  #[clap(long)]
+  pub keep_hyphenated: bool,
+  /// Split words at apostrophes, e.g. "don't" into "don" and "t"
+  #[clap(long)]
+  pub split_apostrophes: bool,
+  /// Language of the text to choose a dictionary for Chinese characters.
+  /// Detected from the text if not set.
+  #[clap(skip)]
+  pub language: Option<Language>,
+}
+
+/// Parts of source code to leave out before searching for duplications.
+/// The language is detected by the file extension.
+#[derive(Args, Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct SkipOptions {
+  /// Ignore comments
+  #[clap(long)]
+  pub skip_comments: bool,
+  /// Ignore the comments at the beginning of a file (e.g. license headers)
+  #[clap(long)]
+  pub skip_license_headers: bool,
+  /// Ignore import statements (e.g. `use`, `import`, `#include`)
+  #[clap(long)]
+  pub skip_imports: bool,
+  /// Ignore the content of string literals
+  #[clap(long)]
+  pub skip_strings: bool,
+}
+
+impl SkipOptions {
+  /// Whether nothing is skipped
+  pub fn is_empty(&self) -> bool {
+    *self == SkipOptions::default()
+  }
+}
+
+/// Limits for the amount of duplicated code.
+/// Exceeding one makes the command fail.
+#[derive(Args, Debug, Default, Clone, Copy, PartialEq)]
+pub struct ThresholdOptions {
+  /// Maximum number of duplications
+  #[clap(long, value_name = "N")]
+  pub max_duplications: Option<usize>,
+  /// Maximum number of duplicated non-empty lines of all copies
+  #[clap(long, value_name = "N")]
+  pub max_duplicated_lines: Option<usize>,
+  /// Maximum percentage of duplicated non-empty lines
+  #[clap(long, value_name = "P")]
+  pub max_percentage: Option<f64>,
+}
+
+impl ThresholdOptions {
+  /// Whether no threshold is set
+  pub fn is_empty(&self) -> bool {
+    *self == ThresholdOptions::default()
+  }
+}
+
 pub struct Config {
   pub command: Command,
 }
@@ -109,6 +444,23 @@ pub struct LineEntry {
 pub struct FrequencyItem {
   pub word: String,
   pub count: i32,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub forms: Option<Vec<String>>,
+  /// Number of cut off entries summed up in the "(others)" bucket
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub others: Option<usize>,
+}
+
+// Helper type for JSON serialization of n-gram frequency maps
+#[derive(Serialize)]
+pub struct NGramFrequencyItem {
+  pub ngram: Vec<String>,
+  pub count: i32,
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub forms: Option<Vec<String>>,
+  /// Number of cut off entries summed up in the "(others)" bucket
+  #[serde(skip_serializing_if = "Option::is_none")]
+  pub others: Option<usize>,
 }
 
 // Helper type for JSON serialization of line length histograms
@@ -118,20 +470,332 @@ pub struct LineLengthItem {
   pub count: usize,
 }
 
+/// Duplicated content with the path, first line, and last line of each copy
+pub type Duplication = (String, Vec<(String, u32, u32)>);
+
 /// JSON-friendly location for a duplication occurrence
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct DuplicationLocation {
   /// Path to the file containing the duplication
   pub path: String,
   /// Line number where the duplication occurs
   pub line: u32,
+  /// Line number of the last line of the duplication
+  #[serde(default)]
+  pub end_line: u32,
 }
 
 /// JSON-friendly representation of a duplicated block
-#[derive(Serialize)]
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
 pub struct DuplicationItem {
   /// The duplicated content block
   pub content: String,
   /// All file locations where this block appears
   pub locations: Vec<DuplicationLocation>,
 }
+
+/// Known duplication in a baseline file
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct BaselineItem {
+  /// Hash of the content, which doesn't change when the block is moved.
+  /// Computed from the content if missing.
+  #[serde(default)]
+  pub hash: String,
+  /// Number of copies, so that additional copies are reported.
+  /// Taken from the locations if missing.
+  #[serde(default)]
+  pub copies: usize,
+  #[serde(flatten)]
+  pub duplication: DuplicationItem,
+}
+
+/// Number of non-empty lines and how many of them are duplicated
+#[derive(Serialize, Debug, Default, Clone, Copy, PartialEq)]
+pub struct DuplicatedLines {
+  pub lines: usize,
+  pub duplicated_lines: usize,
+  /// Share of duplicated lines in percent
+  pub percentage: f64,
+}
+
+/// Duplicated lines of a file or directory
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DuplicatedLinesItem {
+  pub path: String,
+  #[serde(flatten)]
+  pub lines: DuplicatedLines,
+}
+
+/// Share of duplicated lines per file, per directory, and in total
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DuplicationReport {
+  /// Files with duplicated lines
+  pub files: Vec<DuplicatedLinesItem>,
+  /// Directories with duplicated lines
+  pub directories: Vec<DuplicatedLinesItem>,
+  /// All analyzed files
+  pub total: DuplicatedLines,
+}
+
+/// JSON output of duplications with a report of the duplicated lines
+#[derive(Serialize)]
+pub struct DuplicationsWithReport<T> {
+  pub duplications: Vec<T>,
+  pub report: DuplicationReport,
+}
+
+/// Line which only occurs in one of two similar blocks
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct LineDifference {
+  pub path: String,
+  pub line: u32,
+  /// The line without leading and trailing whitespace
+  pub content: String,
+}
+
+/// JSON-friendly representation of two nearly identical blocks
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct SimilarDuplicationItem {
+  /// The content of the first block
+  pub content: String,
+  /// Token-based similarity of the two blocks between 0 and 1
+  pub similarity: f64,
+  /// Locations of the two blocks
+  pub locations: Vec<DuplicationLocation>,
+  /// Lines which differ between the two blocks
+  pub differences: Vec<LineDifference>,
+}
+
+/// Scores of common readability formulas
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct ReadabilityScores {
+  pub flesch_reading_ease: f64,
+  pub flesch_kincaid_grade: f64,
+  pub gunning_fog: f64,
+  pub smog: f64,
+  pub coleman_liau: f64,
+  pub automated_readability_index: f64,
+}
+
+/// JSON-friendly readability report of a file
+#[derive(Serialize)]
+pub struct ReadabilityItem {
+  /// Path to the analyzed file
+  pub path: String,
+  pub sentences: usize,
+  pub words: usize,
+  pub syllables: usize,
+  #[serde(flatten)]
+  pub scores: ReadabilityScores,
+}
+
+/// General statistics of a text
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct TextStats {
+  pub words: usize,
+  pub characters: usize,
+  pub characters_no_whitespace: usize,
+  pub sentences: usize,
+  pub paragraphs: usize,
+  pub unique_words: usize,
+  /// Average number of letters and digits per word
+  pub average_word_length: f64,
+  /// Average number of words per sentence
+  pub average_sentence_length: f64,
+  /// Estimated time to read the text silently
+  pub reading_time_seconds: f64,
+  /// Estimated time to read the text aloud
+  pub speaking_time_seconds: f64,
+}
+
+/// JSON-friendly statistics of a file
+#[derive(Serialize)]
+pub struct StatsItem {
+  /// Path to the analyzed file
+  pub path: String,
+  #[serde(flatten)]
+  pub stats: TextStats,
+}
+
+/// JSON-friendly statistics of all files and their total
+#[derive(Serialize)]
+pub struct StatsReport {
+  pub files: Vec<StatsItem>,
+  pub total: TextStats,
+}
+
+/// Vocabulary richness and lexical diversity metrics of a text
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct LexicalDiversity {
+  /// Number of words
+  pub tokens: usize,
+  /// Number of distinct words
+  pub types: usize,
+  pub type_token_ratio: f64,
+  /// Average type-token ratio of a window moving over the text
+  pub moving_average_ttr: f64,
+  /// Measure of textual lexical diversity
+  /// (`null` if no word is repeated)
+  pub mtld: Option<f64>,
+  pub yules_k: f64,
+  /// Honoré's statistic (`null` if every word occurs only once)
+  pub honores_r: Option<f64>,
+  /// Number of words which occur exactly once
+  pub hapax_legomena: usize,
+  /// Number of words which occur exactly twice
+  pub dis_legomena: usize,
+}
+
+/// JSON-friendly lexical diversity of a file
+#[derive(Serialize)]
+pub struct DiversityItem {
+  /// Path to the analyzed file
+  pub path: String,
+  #[serde(flatten)]
+  pub diversity: LexicalDiversity,
+}
+
+/// Parameters of the fit `count = constant / (rank + offset)^exponent`
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct ZipfFit {
+  pub exponent: f64,
+  /// Offset of the rank (0 for Zipf's law)
+  pub offset: f64,
+  pub constant: f64,
+  /// Coefficient of determination on the log-log scale
+  pub r_squared: f64,
+}
+
+/// Word whose frequency deviates from the fit
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ZipfDeviation {
+  pub rank: usize,
+  pub word: String,
+  pub count: i32,
+  pub expected: f64,
+  /// Observed count divided by the expected count
+  pub ratio: f64,
+}
+
+/// Zipf's law and Zipf-Mandelbrot law fits of a frequency map
+#[derive(Serialize, Debug, Default, Clone, PartialEq)]
+pub struct ZipfReport {
+  pub words: usize,
+  pub distinct_words: usize,
+  pub zipf: ZipfFit,
+  pub mandelbrot: ZipfFit,
+  pub deviations: Vec<ZipfDeviation>,
+}
+
+/// TF-IDF score of a term in a document
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct KeywordScore {
+  pub term: String,
+  /// Number of occurrences in the document
+  pub count: i32,
+  pub tf_idf: f64,
+}
+
+/// The most distinctive terms of a document
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct DocumentKeywords {
+  /// Path to the analyzed file
+  pub path: String,
+  pub keywords: Vec<KeywordScore>,
+}
+
+/// Word pair which occurs together more often than by chance
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct CollocationItem {
+  pub words: Vec<String>,
+  /// Number of occurrences of the word pair
+  pub count: i32,
+  /// Association score according to the chosen measure
+  pub score: f64,
+}
+
+/// Word which is more frequent in one of two compared texts
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct KeynessItem {
+  pub word: String,
+  pub count_a: i32,
+  pub count_b: i32,
+  /// Keyness score according to the chosen measure
+  pub score: f64,
+}
+
+/// Differences between the word frequencies of two texts
+#[derive(Serialize)]
+pub struct FrequencyComparison {
+  /// Path to the first text
+  pub a: String,
+  /// Path to the second text
+  pub b: String,
+  pub overrepresented_in_a: Vec<KeynessItem>,
+  pub overrepresented_in_b: Vec<KeynessItem>,
+  pub only_in_a: Vec<FrequencyItem>,
+  pub only_in_b: Vec<FrequencyItem>,
+}
+
+/// Occurrence of a word or phrase with its surrounding words
+#[derive(Serialize, Debug, Clone, PartialEq)]
+pub struct ConcordanceItem {
+  pub path: String,
+  /// Line of the first word of the occurrence
+  pub line: usize,
+  pub left: String,
+  /// The occurrence as written in the text
+  pub keyword: String,
+  pub right: String,
+}
+
+/// Observed and expected frequency of a letter
+#[derive(Serialize, Debug)]
+pub struct CharFrequencyItem {
+  pub letter: char,
+  pub count: usize,
+  /// Observed share of all letters of the reference table in percent
+  pub percentage: f64,
+  /// Share of the letter according to the reference table in percent
+  pub expected_percentage: f64,
+}
+
+/// Comparison of letter frequencies with a reference distribution
+#[derive(Serialize, Debug)]
+pub struct CharFrequencyReport {
+  pub letters: Vec<CharFrequencyItem>,
+  /// Number of letters which are part of the reference table
+  pub total_letters: usize,
+  /// Number of letters which are not part of the reference table
+  pub other_letters: usize,
+  /// Pearson's chi-squared statistic of the observed letter counts
+  pub chi_squared: f64,
+}
+
+/// Similarity of a text with a language from 0 (none) to 1 (equal)
+#[derive(Serialize, Debug, Clone)]
+pub struct LanguageScore {
+  pub language: Language,
+  pub score: f64,
+}
+
+/// The most likely language of a text
+#[derive(Serialize, Debug, Clone)]
+pub struct LanguageGuess {
+  pub language: Language,
+  /// How clearly the language stands out from the others (0 to 1)
+  pub confidence: f64,
+  /// Scores of all candidate languages, best first
+  pub scores: Vec<LanguageScore>,
+}
+
+/// JSON-friendly detected language of a file
+#[derive(Serialize)]
+pub struct LanguageDetectionItem {
+  /// Path to the analyzed file
+  pub path: String,
+  /// Detected language or `null` if the file contains no letters
+  pub language: Option<Language>,
+  pub confidence: f64,
+  pub scores: Vec<LanguageScore>,
+}
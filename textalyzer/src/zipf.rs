@@ -0,0 +1,429 @@
+use crate::frequency::select_frequencies;
+use crate::types::{HistogramOptions, ZipfDeviation, ZipfFit, ZipfReport};
+use pad::{Alignment, PadStr};
+use std::collections::HashMap;
+
+/// Largest offset of the Zipf-Mandelbrot fit which is tried
+const MAX_MANDELBROT_OFFSET: f64 = 100.0;
+
+/// Number of iterations of the golden-section search for the offset
+const SEARCH_ITERATIONS: usize = 100;
+
+/// Height of the log-log plot in lines
+const PLOT_HEIGHT: usize = 20;
+
+/// Expected frequency of the given rank according to a fit.
+pub fn expected_count(fit: &ZipfFit, rank: f64) -> f64 {
+  fit.constant / (rank + fit.offset).powf(fit.exponent)
+}
+
+/// Fit `ln(count) = ln(C) - s · ln(rank + offset)`
+/// with a least squares regression for the given offset.
+///
+/// Each rank is weighted by `1 / rank`, so that every order of magnitude
+/// of ranks has the same influence and the long tail of rare words
+/// doesn't dominate the fit.
+fn fit_with_offset(counts: &[i32], offset: f64) -> (ZipfFit, f64) {
+  // (weight, x, y)
+  let points: Vec<(f64, f64, f64)> = counts
+    .iter()
+    .enumerate()
+    .map(|(idx, &count)| {
+      let rank = idx as f64 + 1.0;
+      (1.0 / rank, (rank + offset).ln(), (count as f64).ln())
+    })
+    .collect();
+  let weight_sum: f64 = points.iter().map(|(w, _, _)| w).sum();
+  let mean_x = points.iter().map(|(w, x, _)| w * x).sum::<f64>() / weight_sum;
+  let mean_y = points.iter().map(|(w, _, y)| w * y).sum::<f64>() / weight_sum;
+
+  let covariance: f64 = points
+    .iter()
+    .map(|(w, x, y)| w * (x - mean_x) * (y - mean_y))
+    .sum();
+  let variance: f64 = points
+    .iter()
+    .map(|(w, x, _)| w * (x - mean_x).powi(2))
+    .sum();
+  let slope = if variance == 0.0 {
+    0.0
+  } else {
+    covariance / variance
+  };
+  let intercept = mean_y - slope * mean_x;
+
+  let squared_error: f64 = points
+    .iter()
+    .map(|(w, x, y)| w * (y - (intercept + slope * x)).powi(2))
+    .sum();
+  let total_squares: f64 = points
+    .iter()
+    .map(|(w, _, y)| w * (y - mean_y).powi(2))
+    .sum();
+  let r_squared = if total_squares == 0.0 {
+    1.0
+  } else {
+    1.0 - squared_error / total_squares
+  };
+
+  let fit = ZipfFit {
+    exponent: -slope,
+    offset,
+    constant: intercept.exp(),
+    r_squared,
+  };
+  (fit, squared_error)
+}
+
+/// Fit Zipf's law `count = C / rank^s` to counts sorted in descending order.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::zipf::fit_zipf;
+///
+/// let fit = fit_zipf(&[60, 30, 20, 15, 12]);
+///
+/// assert!((fit.exponent - 1.0).abs() < 1e-9);
+/// assert!((fit.constant - 60.0).abs() < 1e-9);
+/// ```
+pub fn fit_zipf(counts: &[i32]) -> ZipfFit {
+  fit_with_offset(counts, 0.0).0
+}
+
+/// Fit the Zipf-Mandelbrot law `count = C / (rank + b)^s`
+/// to counts sorted in descending order.
+/// The offset `b` is determined with a golden-section search.
+pub fn fit_mandelbrot(counts: &[i32]) -> ZipfFit {
+  let inverse_phi = (5f64.sqrt() - 1.0) / 2.0;
+  let error = |offset: f64| fit_with_offset(counts, offset).1;
+
+  let (mut low, mut high) = (0.0, MAX_MANDELBROT_OFFSET);
+  for _ in 0..SEARCH_ITERATIONS {
+    let left = high - inverse_phi * (high - low);
+    let right = low + inverse_phi * (high - low);
+    if error(left) < error(right) {
+      high = right;
+    } else {
+      low = left;
+    }
+  }
+
+  fit_with_offset(counts, (low + high) / 2.0).0
+}
+
+/// Fit Zipf's law and the Zipf-Mandelbrot law to a frequency map
+/// and find the words which deviate most from the Zipf-Mandelbrot fit.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::frequency::generate_frequency_map;
+/// use textalyzer::zipf::analyze_zipf;
+///
+/// let report = analyze_zipf(generate_frequency_map("a a a a b b c"), 1);
+///
+/// assert_eq!(report.distinct_words, 3);
+/// assert!(report.zipf.exponent > 0.0);
+/// ```
+pub fn analyze_zipf(
+  freq_map: HashMap<String, i32>,
+  deviations: usize,
+) -> ZipfReport {
+  let entries =
+    select_frequencies(freq_map, &HistogramOptions::default()).entries;
+  let counts: Vec<i32> = entries.iter().map(|(_, count)| *count).collect();
+
+  if counts.is_empty() {
+    return ZipfReport::default();
+  }
+
+  let zipf = fit_zipf(&counts);
+  let mandelbrot = fit_mandelbrot(&counts);
+
+  let mut all_deviations: Vec<ZipfDeviation> = entries
+    .iter()
+    .enumerate()
+    .map(|(idx, (word, count))| {
+      let expected = expected_count(&mandelbrot, idx as f64 + 1.0);
+      ZipfDeviation {
+        rank: idx + 1,
+        word: word.clone(),
+        count: *count,
+        expected,
+        ratio: *count as f64 / expected,
+      }
+    })
+    .collect();
+  // Sort by the Pearson residual, so that a frequent word which is
+  // 50 % too common ranks before a rare word which occurs once instead of twice
+  let residual = |deviation: &ZipfDeviation| {
+    (deviation.count as f64 - deviation.expected).abs()
+      / deviation.expected.sqrt()
+  };
+  all_deviations.sort_by(|a, b| {
+    residual(b)
+      .total_cmp(&residual(a))
+      .then(a.rank.cmp(&b.rank))
+  });
+  all_deviations.truncate(deviations);
+
+  ZipfReport {
+    words: counts.iter().map(|&count| count as usize).sum(),
+    distinct_words: counts.len(),
+    zipf,
+    mandelbrot,
+    deviations: all_deviations,
+  }
+}
+
+/// Format a Zipf report as a summary and a table of the largest deviations.
+pub fn format_zipf(report: &ZipfReport) -> String {
+  let mut result = format!(
+    "Words: {} ({} distinct)\n\
+    Zipf:            s = {:.3}, R² = {:.4}\n\
+    Zipf-Mandelbrot: s = {:.3}, b = {:.3}, R² = {:.4}\n",
+    report.words,
+    report.distinct_words,
+    report.zipf.exponent,
+    report.zipf.r_squared,
+    report.mandelbrot.exponent,
+    report.mandelbrot.offset,
+    report.mandelbrot.r_squared,
+  );
+
+  if report.deviations.is_empty() {
+    return result;
+  }
+
+  let word_width = report
+    .deviations
+    .iter()
+    .map(|deviation| deviation.word.chars().count())
+    .max()
+    .unwrap_or(0)
+    .max("Word".len());
+  let columns = ["Rank", "Count", "Expected", "Ratio"];
+
+  result += &format!(
+    "\nLargest deviations from the Zipf-Mandelbrot fit\n{}  {}\n",
+    "Word".pad_to_width_with_alignment(word_width, Alignment::Left),
+    columns.join("  "),
+  );
+  for deviation in &report.deviations {
+    let values = [
+      deviation.rank.to_string(),
+      deviation.count.to_string(),
+      format!("{:.1}", deviation.expected),
+      format!("{:.2}", deviation.ratio),
+    ];
+    result += &format!(
+      "{}  {}\n",
+      deviation
+        .word
+        .pad_to_width_with_alignment(word_width, Alignment::Left),
+      values
+        .iter()
+        .zip(columns)
+        .map(|(value, column)| {
+          value.pad_to_width_with_alignment(column.len(), Alignment::Right)
+        })
+        .collect::<Vec<_>>()
+        .join("  "),
+    );
+  }
+
+  result
+}
+
+/// Quote a CSV field if it contains a separator, a quote, or a line break.
+fn csv_field(value: &str) -> String {
+  if value.contains([',', '"', '\n', '\r']) {
+    format!("\"{}\"", value.replace('"', "\"\""))
+  } else {
+    value.to_string()
+  }
+}
+
+/// Export the rank/frequency pairs of a frequency map as CSV
+/// together with the expected counts of the Zipf-Mandelbrot fit.
+///
+/// # Examples
+///
+/// ```rust
+/// use textalyzer::frequency::generate_frequency_map;
+/// use textalyzer::zipf::format_zipf_csv;
+///
+/// let csv = format_zipf_csv(generate_frequency_map("b a b"));
+///
+/// assert!(csv.starts_with("rank,word,count,expected\n1,b,2,"));
+/// ```
+pub fn format_zipf_csv(freq_map: HashMap<String, i32>) -> String {
+  let entries =
+    select_frequencies(freq_map, &HistogramOptions::default()).entries;
+  let counts: Vec<i32> = entries.iter().map(|(_, count)| *count).collect();
+  let fit = fit_mandelbrot(&counts);
+
+  let mut result = String::from("rank,word,count,expected\n");
+  for (idx, (word, count)) in entries.iter().enumerate() {
+    let rank = idx + 1;
+    result += &format!(
+      "{rank},{},{count},{:.4}\n",
+      csv_field(word),
+      expected_count(&fit, rank as f64),
+    );
+  }
+
+  result
+}
+
+/// Plot the rank/frequency pairs of a frequency map on a log-log scale.
+/// Observed counts are drawn as `*`, the Zipf-Mandelbrot fit as `·`.
+pub fn plot_zipf(freq_map: HashMap<String, i32>, width: usize) -> String {
+  let entries =
+    select_frequencies(freq_map, &HistogramOptions::default()).entries;
+  let counts: Vec<i32> = entries.iter().map(|(_, count)| *count).collect();
+  let Some(&max_count) = counts.first() else {
+    return String::new();
+  };
+  let fit = fit_mandelbrot(&counts);
+
+  let label_width = max_count.to_string().len();
+  let plot_width = width.saturating_sub(label_width + 2).max(10);
+  let max_x = (counts.len() as f64).ln().max(f64::EPSILON);
+  let max_y = (max_count as f64).ln().max(f64::EPSILON);
+
+  let mut grid = vec![vec![' '; plot_width]; PLOT_HEIGHT];
+  let mut plot = |log_rank: f64, log_count: f64, symbol: char| {
+    if !(0.0..=max_y).contains(&log_count) {
+      return;
+    }
+    let column = (log_rank / max_x * (plot_width - 1) as f64).round() as usize;
+    let row = PLOT_HEIGHT
+      - 1
+      - (log_count / max_y * (PLOT_HEIGHT - 1) as f64).round() as usize;
+    grid[row][column.min(plot_width - 1)] = symbol;
+  };
+
+  for column in 0..plot_width {
+    let log_rank = column as f64 / (plot_width - 1) as f64 * max_x;
+    let expected = expected_count(&fit, log_rank.exp());
+    plot(log_rank, expected.ln(), '·');
+  }
+  for (idx, &count) in counts.iter().enumerate() {
+    plot((idx as f64 + 1.0).ln(), (count as f64).ln(), '*');
+  }
+
+  let mut result = String::new();
+  for (idx, row) in grid.iter().enumerate() {
+    let label = match idx {
+      0 => max_count.to_string(),
+      _ if idx == PLOT_HEIGHT - 1 => "1".to_string(),
+      _ => String::new(),
+    };
+    result += &format!(
+      "{} │{}\n",
+      label.pad_to_width_with_alignment(label_width, Alignment::Right),
+      row.iter().collect::<String>().trim_end(),
+    );
+  }
+
+  let last_rank = counts.len().to_string();
+  result += &format!(
+    "{} └{}\n{}  1{}\n",
+    " ".repeat(label_width),
+    "─".repeat(plot_width),
+    " ".repeat(label_width),
+    last_rank.pad_to_width_with_alignment(plot_width - 1, Alignment::Right),
+  );
+  result += &format!(
+    "{}  Frequency by rank (log-log), * observed, · Zipf-Mandelbrot fit\n",
+    " ".repeat(label_width),
+  );
+
+  result
+}
+
+#[cfg(test)]
+mod tests {
+  use super::*;
+
+  fn zipf_counts(exponent: f64, offset: f64, length: usize) -> Vec<i32> {
+    (1..=length)
+      .map(|rank| (10_000.0 / (rank as f64 + offset).powf(exponent)) as i32)
+      .collect()
+  }
+
+  fn freq_map(counts: &[(&str, i32)]) -> HashMap<String, i32> {
+    counts
+      .iter()
+      .map(|(word, count)| (word.to_string(), *count))
+      .collect()
+  }
+
+  #[test]
+  fn test_fit_zipf() {
+    let fit = fit_zipf(&zipf_counts(1.2, 0.0, 50));
+
+    assert!((fit.exponent - 1.2).abs() < 0.01, "{fit:?}");
+    assert!(fit.r_squared > 0.999);
+  }
+
+  #[test]
+  fn test_fit_mandelbrot() {
+    let fit = fit_mandelbrot(&zipf_counts(1.1, 2.7, 200));
+
+    assert!((fit.exponent - 1.1).abs() < 0.05, "{fit:?}");
+    assert!((fit.offset - 2.7).abs() < 0.3, "{fit:?}");
+    assert!(fit.r_squared > fit_zipf(&zipf_counts(1.1, 2.7, 200)).r_squared);
+  }
+
+  #[test]
+  fn test_analyze_zipf_deviations() {
+    let mut counts: Vec<(String, i32)> = zipf_counts(1.0, 0.0, 20)
+      .into_iter()
+      .enumerate()
+      .map(|(idx, count)| (format!("w{idx:02}"), count))
+      .collect();
+    // 1100 instead of 1000 at rank 10
+    counts[9].1 = 1100;
+    let report = analyze_zipf(counts.into_iter().collect(), 1);
+
+    assert_eq!(report.distinct_words, 20);
+    assert_eq!(report.deviations.len(), 1);
+    assert_eq!(report.deviations[0].word, "w09");
+    assert!(report.deviations[0].ratio > 1.0);
+  }
+
+  #[test]
+  fn test_analyze_zipf_empty() {
+    let report = analyze_zipf(HashMap::new(), 10);
+
+    assert_eq!(report.distinct_words, 0);
+    assert!(report.deviations.is_empty());
+  }
+
+  #[test]
+  fn test_format_zipf_csv_quotes_fields() {
+    let csv = format_zipf_csv(freq_map(&[("1,000", 2), ("a\"b", 1)]));
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert!(lines[1].starts_with("1,\"1,000\",2,"));
+    assert!(lines[2].starts_with("2,\"a\"\"b\",1,"));
+  }
+
+  #[test]
+  fn test_plot_zipf() {
+    let counts: Vec<(String, i32)> = zipf_counts(1.0, 0.0, 100)
+      .into_iter()
+      .enumerate()
+      .map(|(idx, count)| (format!("w{idx}"), count))
+      .collect();
+    let plot = plot_zipf(counts.into_iter().collect(), 60);
+    let lines: Vec<&str> = plot.lines().collect();
+
+    assert_eq!(lines.len(), PLOT_HEIGHT + 3);
+    assert!(lines[0].starts_with("10000 │*"));
+    assert!(lines[PLOT_HEIGHT + 1].ends_with("100"));
+  }
+}
@@ -1,7 +1,8 @@
 extern crate textalyzer;
 
+use std::io::Write;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 #[test]
 fn it_can_be_called_with_histogram_args() {
@@ -11,13 +12,13 @@ fn it_can_be_called_with_histogram_args() {
   let example_path = root_dir.join("examples/1984.txt");
 
   let output = Command::new(exe_path)
-    .args(&["histogram", example_path.to_str().unwrap()])
+    .args(["histogram", example_path.to_str().unwrap()])
     .output()
     .expect("failed to execute process");
 
   assert_eq!(
     String::from_utf8_lossy(&output.stdout).len(),
-    239902,
+    242512,
     "\n\nERROR:\n{}",
     String::from_utf8_lossy(&output.stderr),
   );
@@ -32,7 +33,7 @@ fn it_can_be_called_with_duplication_multiple_paths() {
   let example_path2 = root_dir.join("examples/herr_von_ribbeck.txt");
 
   let output = Command::new(exe_path)
-    .args(&[
+    .args([
       "duplication",
       example_path1.to_str().unwrap(),
       example_path2.to_str().unwrap(),
@@ -48,3 +49,175 @@ fn it_can_be_called_with_duplication_multiple_paths() {
     String::from_utf8_lossy(&output.stderr),
   );
 }
+
+#[test]
+fn it_can_be_called_with_readability_args() {
+  let root_dir = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+  let exe_path = root_dir.join("target/debug/textalyzer");
+  let example_path = root_dir.join("examples/1984.txt");
+
+  let output = Command::new(exe_path)
+    .args(["readability", example_path.to_str().unwrap()])
+    .output()
+    .expect("failed to execute process");
+
+  let output_str = String::from_utf8_lossy(&output.stdout);
+  assert!(
+    output_str.contains("Flesch Reading Ease")
+      && output_str.contains("Automated Readability Index"),
+    "\n\nERROR:\n{}",
+    String::from_utf8_lossy(&output.stderr),
+  );
+}
+
+#[test]
+fn it_can_be_called_with_stats_args() {
+  let root_dir = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+  let exe_path = root_dir.join("target/debug/textalyzer");
+  let example_path = root_dir.join("examples/nested");
+
+  let output = Command::new(exe_path)
+    .args(["stats", "--json", example_path.to_str().unwrap()])
+    .output()
+    .expect("failed to execute process");
+
+  let report: serde_json::Value = serde_json::from_slice(&output.stdout)
+    .unwrap_or_else(|_| {
+      panic!("\n\nERROR:\n{}", String::from_utf8_lossy(&output.stderr))
+    });
+  assert_eq!(report["files"].as_array().unwrap().len(), 3);
+  assert!(report["total"]["words"].as_u64().unwrap() > 0);
+}
+
+#[test]
+fn it_can_create_a_histogram_of_several_paths_and_stdin() {
+  let root_dir = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+  let exe_path = root_dir.join("target/debug/textalyzer");
+  let example_path = root_dir.join("examples/nested");
+
+  let mut child = Command::new(exe_path)
+    .args(["histogram", "--json", example_path.to_str().unwrap(), "-"])
+    .stdin(Stdio::piped())
+    .stdout(Stdio::piped())
+    .stderr(Stdio::piped())
+    .spawn()
+    .expect("failed to execute process");
+  child
+    .stdin
+    .take()
+    .unwrap()
+    .write_all(b"This is stdin.")
+    .unwrap();
+  let output = child.wait_with_output().unwrap();
+
+  let items: serde_json::Value = serde_json::from_slice(&output.stdout)
+    .unwrap_or_else(|_| {
+      panic!("\n\nERROR:\n{}", String::from_utf8_lossy(&output.stderr))
+    });
+  let count_of = |word: &str| {
+    items
+      .as_array()
+      .unwrap()
+      .iter()
+      .find(|item| item["word"] == word)
+      .map(|item| item["count"].as_u64().unwrap())
+  };
+  assert_eq!(count_of("this"), Some(11));
+  assert_eq!(count_of("stdin"), Some(1));
+}
+
+#[test]
+fn it_can_limit_the_histogram_to_the_top_entries() {
+  let root_dir = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+  let exe_path = root_dir.join("target/debug/textalyzer");
+  let example_path = root_dir.join("examples/1984.txt");
+
+  let output = Command::new(exe_path)
+    .args(["histogram", "--top", "10", example_path.to_str().unwrap()])
+    .output()
+    .expect("failed to execute process");
+
+  let output_str = String::from_utf8_lossy(&output.stdout);
+  let lines: Vec<&str> = output_str.lines().collect();
+  assert_eq!(
+    lines.len(),
+    12, // 10 entries, "(others)", and a trailing empty line
+    "\n\nERROR:\n{}",
+    String::from_utf8_lossy(&output.stderr),
+  );
+  assert!(lines[0].trim_start().starts_with("the "));
+  assert!(lines[10].starts_with("(others)"));
+}
+
+#[test]
+fn it_only_reports_duplications_missing_in_the_baseline() {
+  let root_dir = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+  let exe_path = root_dir.join("target/debug/textalyzer");
+  let example_path = root_dir.join("examples/duplicates.py");
+  let temp_dir = tempfile::tempdir().unwrap();
+  let baseline_path = temp_dir.path().join("baseline.json");
+
+  let run = |extra_args: &[&str]| {
+    let output = Command::new(&exe_path)
+      .args(["duplication", example_path.to_str().unwrap()])
+      .args(["--baseline", baseline_path.to_str().unwrap()])
+      .args(extra_args)
+      .output()
+      .expect("failed to execute process");
+    assert!(
+      output.status.success(),
+      "\n\nERROR:\n{}",
+      String::from_utf8_lossy(&output.stderr),
+    );
+    String::from_utf8_lossy(&output.stdout).to_string()
+  };
+
+  let written = run(&["--write-baseline"]);
+  assert!(written.contains("duplications to the baseline"));
+
+  let output = run(&[]);
+  assert!(output.contains("Skipped"));
+  assert!(output.contains("No duplications found."));
+}
+
+#[test]
+fn it_fails_with_exit_code_3_if_a_duplication_threshold_is_exceeded() {
+  let root_dir = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+  let exe_path = root_dir.join("target/debug/textalyzer");
+  let example_path = root_dir.join("examples/duplicates.py");
+
+  let run = |max_duplications: &str| {
+    Command::new(&exe_path)
+      .args(["duplication", example_path.to_str().unwrap()])
+      .args(["--max-duplications", max_duplications])
+      .output()
+      .expect("failed to execute process")
+  };
+
+  let output = run("10");
+  assert_eq!(output.status.code(), Some(0));
+
+  let output = run("0");
+  assert_eq!(output.status.code(), Some(3));
+  assert!(String::from_utf8_lossy(&output.stderr)
+    .contains("duplications, more than the maximum of 0"));
+}
+
+#[test]
+fn it_reports_the_share_of_duplicated_lines_as_json() {
+  let root_dir = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap();
+  let exe_path = root_dir.join("target/debug/textalyzer");
+  let example_path = root_dir.join("examples/duplicates.py");
+
+  let output = Command::new(exe_path)
+    .args(["duplication", "--report", "--json"])
+    .arg(example_path.to_str().unwrap())
+    .output()
+    .expect("failed to execute process");
+
+  let stdout = String::from_utf8_lossy(&output.stdout);
+  let json: serde_json::Value = serde_json::from_str(&stdout).unwrap();
+  assert!(json["duplications"].is_array());
+  assert_eq!(json["report"]["files"][0]["duplicated_lines"], 8);
+  assert_eq!(json["report"]["total"]["lines"], 42);
+}